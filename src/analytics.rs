@@ -0,0 +1,17 @@
+//! Counting analytics on top of tracker output.
+//!
+//! Counters consume the tracks returned by `BYTETracker::update` each frame,
+//! place every track at an anchor point on its box, and turn its movement
+//! into line-crossing or zone enter/exit events. A hysteresis band around
+//! each line or zone boundary keeps jittery boxes from being counted twice.
+
+mod line;
+mod zone;
+
+pub use crate::tracker::{Anchor, Polygon};
+pub use line::{CountingLine, CrossingDirection, LineCounter, LineCrossing};
+pub use zone::{ZoneCounter, ZoneEvent, ZoneEventKind};
+
+/// Default number of frames a track may go unseen before its counter state
+/// is dropped; matches the default `TrackerConfig::track_buffer`.
+const DEFAULT_MAX_IDLE: u32 = 30;
@@ -0,0 +1,255 @@
+//! Directed line-crossing counter.
+
+use std::collections::HashMap;
+
+use super::DEFAULT_MAX_IDLE;
+use crate::tracker::{Anchor, BoxGeometry, STrack};
+
+/// Line segment that tracks are counted crossing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CountingLine {
+    pub start: (f32, f32),
+    pub end: (f32, f32),
+}
+
+impl CountingLine {
+    pub fn new(start: (f32, f32), end: (f32, f32)) -> Self {
+        Self { start, end }
+    }
+
+    /// Signed distance of `point` from the infinite line through the
+    /// segment: positive on the right-hand side when walking from `start`
+    /// to `end` in image coordinates (y pointing down).
+    pub fn signed_distance(&self, (px, py): (f32, f32)) -> f32 {
+        let (dx, dy) = (self.end.0 - self.start.0, self.end.1 - self.start.1);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len == 0.0 {
+            return 0.0;
+        }
+        (dx * (py - self.start.1) - dy * (px - self.start.0)) / len
+    }
+
+    /// Whether the path `from` -> `to`, which crosses the infinite line,
+    /// does so within the segment.
+    fn crosses_segment(&self, from: (f32, f32), to: (f32, f32)) -> bool {
+        let (d0, d1) = (self.signed_distance(from), self.signed_distance(to));
+        if d0 == d1 {
+            return false;
+        }
+        let s = d0 / (d0 - d1);
+        let hit = (from.0 + s * (to.0 - from.0), from.1 + s * (to.1 - from.1));
+        let (dx, dy) = (self.end.0 - self.start.0, self.end.1 - self.start.1);
+        let t = ((hit.0 - self.start.0) * dx + (hit.1 - self.start.1) * dy) / (dx * dx + dy * dy);
+        (0.0..=1.0).contains(&t)
+    }
+}
+
+/// Direction of a crossing relative to the line's `start` -> `end` vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossingDirection {
+    /// From the left-hand to the right-hand side.
+    Forward,
+    /// From the right-hand to the left-hand side.
+    Backward,
+}
+
+/// A track crossing the counting line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineCrossing {
+    pub track_id: u64,
+    pub frame_id: u32,
+    pub direction: CrossingDirection,
+}
+
+/// Last confirmed side of the line for one track.
+#[derive(Debug, Clone, Copy)]
+struct LineState {
+    /// `true` for the right-hand side.
+    right: bool,
+    /// Anchor when the side was last confirmed.
+    point: (f32, f32),
+    last_seen: u32,
+}
+
+/// Counts tracks crossing a line in each direction.
+///
+/// A track's side only changes once its anchor is more than `hysteresis`
+/// pixels past the line, so a box jittering on the line is counted once.
+#[derive(Debug, Clone)]
+pub struct LineCounter {
+    line: CountingLine,
+    anchor: Anchor,
+    hysteresis: f32,
+    max_idle: u32,
+    states: HashMap<u64, LineState>,
+    forward: u64,
+    backward: u64,
+}
+
+impl LineCounter {
+    pub fn new(line: CountingLine) -> Self {
+        Self {
+            line,
+            anchor: Anchor::default(),
+            hysteresis: 0.0,
+            max_idle: DEFAULT_MAX_IDLE,
+            states: HashMap::new(),
+            forward: 0,
+            backward: 0,
+        }
+    }
+
+    /// Set the point on each box that is tested against the line.
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Set the half-width, in pixels, of the band around the line in which
+    /// a track keeps its previous side.
+    pub fn with_hysteresis(mut self, pixels: f32) -> Self {
+        self.hysteresis = pixels;
+        self
+    }
+
+    /// Set how many frames a track may go unseen before it is forgotten.
+    pub fn with_max_idle(mut self, frames: u32) -> Self {
+        self.max_idle = frames;
+        self
+    }
+
+    /// Process one frame of tracker output.
+    ///
+    /// # Returns
+    /// Crossings that happened in this frame, in track order.
+    pub fn update<B: BoxGeometry>(
+        &mut self,
+        tracks: &[STrack<B>],
+        frame_id: u32,
+    ) -> Vec<LineCrossing> {
+        let mut crossings = Vec::new();
+        for track in tracks {
+            let point = self.anchor.point(&track.rect());
+            let distance = self.line.signed_distance(point);
+            let side = if distance > self.hysteresis {
+                Some(true)
+            } else if distance < -self.hysteresis {
+                Some(false)
+            } else {
+                None
+            };
+
+            match (self.states.get_mut(&track.track_id), side) {
+                (Some(state), Some(right)) => {
+                    if state.right != right && self.line.crosses_segment(state.point, point) {
+                        let direction = if right {
+                            self.forward += 1;
+                            CrossingDirection::Forward
+                        } else {
+                            self.backward += 1;
+                            CrossingDirection::Backward
+                        };
+                        crossings.push(LineCrossing {
+                            track_id: track.track_id,
+                            frame_id,
+                            direction,
+                        });
+                    }
+                    *state = LineState {
+                        right,
+                        point,
+                        last_seen: frame_id,
+                    };
+                }
+                (Some(state), None) => state.last_seen = frame_id,
+                (None, Some(right)) => {
+                    self.states.insert(
+                        track.track_id,
+                        LineState {
+                            right,
+                            point,
+                            last_seen: frame_id,
+                        },
+                    );
+                }
+                (None, None) => {}
+            }
+        }
+
+        let max_idle = self.max_idle;
+        self.states
+            .retain(|_, s| frame_id.saturating_sub(s.last_seen) <= max_idle);
+        crossings
+    }
+
+    pub fn line(&self) -> &CountingLine {
+        &self.line
+    }
+
+    /// Number of left-to-right crossings so far.
+    pub fn forward_count(&self) -> u64 {
+        self.forward
+    }
+
+    /// Number of right-to-left crossings so far.
+    pub fn backward_count(&self) -> u64 {
+        self.backward
+    }
+
+    /// Reset the counts, keeping per-track state.
+    pub fn reset_counts(&mut self) {
+        self.forward = 0;
+        self.backward = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::Rect;
+
+    fn track_at(track_id: u64, cx: f32, bottom: f32) -> STrack {
+        let mut track = STrack::new(Rect::new(cx - 10.0, bottom - 40.0, 20.0, 40.0), 0.9);
+        track.track_id = track_id;
+        track
+    }
+
+    #[test]
+    fn test_directed_crossings() {
+        // Horizontal line left to right: the right-hand side is below it.
+        let mut counter =
+            LineCounter::new(CountingLine::new((0.0, 100.0), (200.0, 100.0))).with_hysteresis(5.0);
+
+        let mut events = Vec::new();
+        for (frame, y) in [(1, 80.0), (2, 95.0), (3, 102.0), (4, 98.0), (5, 110.0)] {
+            events.extend(counter.update(&[track_at(1, 50.0, y)], frame));
+        }
+        assert_eq!(
+            events,
+            vec![LineCrossing {
+                track_id: 1,
+                frame_id: 5,
+                direction: CrossingDirection::Forward,
+            }]
+        );
+
+        counter.update(&[track_at(1, 50.0, 90.0)], 6);
+        assert_eq!((counter.forward_count(), counter.backward_count()), (1, 1));
+    }
+
+    #[test]
+    fn test_crossing_outside_segment_is_ignored() {
+        let mut counter = LineCounter::new(CountingLine::new((0.0, 100.0), (200.0, 100.0)));
+        counter.update(&[track_at(1, 300.0, 80.0)], 1);
+        assert!(counter.update(&[track_at(1, 300.0, 120.0)], 2).is_empty());
+    }
+
+    #[test]
+    fn test_idle_tracks_are_forgotten() {
+        let mut counter =
+            LineCounter::new(CountingLine::new((0.0, 100.0), (200.0, 100.0))).with_max_idle(2);
+        counter.update(&[track_at(1, 50.0, 80.0)], 1);
+        counter.update(&[] as &[STrack], 5);
+        assert!(counter.update(&[track_at(1, 50.0, 120.0)], 6).is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+//! Polygon zone occupancy and enter/exit events.
+
+use std::collections::HashMap;
+
+use super::DEFAULT_MAX_IDLE;
+use crate::tracker::{Anchor, BoxGeometry, Polygon, STrack};
+
+/// Kind of zone event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneEventKind {
+    Enter,
+    Exit,
+}
+
+/// A track entering or leaving a zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZoneEvent {
+    pub track_id: u64,
+    pub frame_id: u32,
+    pub kind: ZoneEventKind,
+    /// Frames spent inside the zone; `0` for `Enter` events.
+    pub dwell_frames: u32,
+}
+
+/// Zone membership for one track.
+#[derive(Debug, Clone, Copy)]
+struct ZoneState {
+    inside: bool,
+    entered: u32,
+    last_seen: u32,
+}
+
+/// Tracks which objects are inside a polygon zone and for how long.
+///
+/// A track only changes between inside and outside once its anchor is more
+/// than `hysteresis` pixels from the zone boundary. Tracks that disappear
+/// while inside exit after `max_idle` frames.
+#[derive(Debug, Clone)]
+pub struct ZoneCounter {
+    polygon: Polygon,
+    anchor: Anchor,
+    hysteresis: f32,
+    max_idle: u32,
+    states: HashMap<u64, ZoneState>,
+    entries: u64,
+    exits: u64,
+}
+
+impl ZoneCounter {
+    pub fn new(polygon: Polygon) -> Self {
+        Self {
+            polygon,
+            anchor: Anchor::default(),
+            hysteresis: 0.0,
+            max_idle: DEFAULT_MAX_IDLE,
+            states: HashMap::new(),
+            entries: 0,
+            exits: 0,
+        }
+    }
+
+    /// Set the point on each box that is tested against the zone.
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Set the half-width, in pixels, of the band around the boundary in
+    /// which a track keeps its previous membership.
+    pub fn with_hysteresis(mut self, pixels: f32) -> Self {
+        self.hysteresis = pixels;
+        self
+    }
+
+    /// Set how many frames a track may go unseen before it is forgotten.
+    pub fn with_max_idle(mut self, frames: u32) -> Self {
+        self.max_idle = frames;
+        self
+    }
+
+    /// Process one frame of tracker output.
+    ///
+    /// # Returns
+    /// Enter and exit events from this frame, including exits of tracks
+    /// that went unseen for longer than `max_idle` frames.
+    pub fn update<B: BoxGeometry>(
+        &mut self,
+        tracks: &[STrack<B>],
+        frame_id: u32,
+    ) -> Vec<ZoneEvent> {
+        let mut events = Vec::new();
+        for track in tracks {
+            let point = self.anchor.point(&track.rect());
+            let contained = self.polygon.contains(point);
+            let decisive = self.polygon.distance_to_boundary(point) > self.hysteresis;
+
+            let state = self.states.entry(track.track_id).or_insert(ZoneState {
+                inside: false,
+                entered: frame_id,
+                last_seen: frame_id,
+            });
+            state.last_seen = frame_id;
+            if !decisive || contained == state.inside {
+                continue;
+            }
+
+            state.inside = contained;
+            let event = if contained {
+                state.entered = frame_id;
+                self.entries += 1;
+                ZoneEvent {
+                    track_id: track.track_id,
+                    frame_id,
+                    kind: ZoneEventKind::Enter,
+                    dwell_frames: 0,
+                }
+            } else {
+                self.exits += 1;
+                ZoneEvent {
+                    track_id: track.track_id,
+                    frame_id,
+                    kind: ZoneEventKind::Exit,
+                    dwell_frames: frame_id - state.entered,
+                }
+            };
+            events.push(event);
+        }
+
+        let max_idle = self.max_idle;
+        let mut expired = Vec::new();
+        self.states.retain(|&track_id, state| {
+            let keep = frame_id.saturating_sub(state.last_seen) <= max_idle;
+            if !keep && state.inside {
+                expired.push(ZoneEvent {
+                    track_id,
+                    frame_id,
+                    kind: ZoneEventKind::Exit,
+                    dwell_frames: state.last_seen - state.entered,
+                });
+            }
+            keep
+        });
+        expired.sort_by_key(|e| e.track_id);
+        self.exits += expired.len() as u64;
+        events.extend(expired);
+        events
+    }
+
+    pub fn polygon(&self) -> &Polygon {
+        &self.polygon
+    }
+
+    /// Number of tracks currently inside the zone.
+    pub fn occupancy(&self) -> usize {
+        self.states.values().filter(|s| s.inside).count()
+    }
+
+    /// IDs of tracks currently inside the zone, sorted.
+    pub fn occupants(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = self
+            .states
+            .iter()
+            .filter(|(_, s)| s.inside)
+            .map(|(&id, _)| id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Frames a track has been inside the zone, up to when it was last seen.
+    pub fn dwell_frames(&self, track_id: u64) -> Option<u32> {
+        self.states
+            .get(&track_id)
+            .filter(|s| s.inside)
+            .map(|s| s.last_seen - s.entered)
+    }
+
+    /// Number of enter events so far.
+    pub fn total_entries(&self) -> u64 {
+        self.entries
+    }
+
+    /// Number of exit events so far.
+    pub fn total_exits(&self) -> u64 {
+        self.exits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::Rect;
+
+    fn track_at(track_id: u64, cx: f32, cy: f32) -> STrack {
+        let mut track = STrack::new(Rect::new(cx - 10.0, cy - 10.0, 20.0, 20.0), 0.9);
+        track.track_id = track_id;
+        track
+    }
+
+    fn square_zone() -> ZoneCounter {
+        ZoneCounter::new(Polygon::from_rect(&Rect::new(100.0, 100.0, 100.0, 100.0)))
+            .with_anchor(Anchor::Center)
+            .with_hysteresis(5.0)
+    }
+
+    #[test]
+    fn test_enter_dwell_exit() {
+        let mut zone = square_zone();
+        let mut events = Vec::new();
+        // Jitters on the left edge, enters, then leaves on the right.
+        for (frame, x) in [(1, 90.0), (2, 102.0), (3, 97.0), (4, 110.0), (5, 150.0)] {
+            events.extend(zone.update(&[track_at(7, x, 150.0)], frame));
+        }
+        assert_eq!(zone.occupants(), vec![7]);
+        assert_eq!(zone.dwell_frames(7), Some(1));
+        events.extend(zone.update(&[track_at(7, 210.0, 150.0)], 9));
+
+        let kinds: Vec<_> = events.iter().map(|e| (e.kind, e.frame_id)).collect();
+        assert_eq!(
+            kinds,
+            vec![(ZoneEventKind::Enter, 4), (ZoneEventKind::Exit, 9)]
+        );
+        assert_eq!(events[1].dwell_frames, 5);
+        assert_eq!(zone.occupancy(), 0);
+    }
+
+    #[test]
+    fn test_vanished_track_exits() {
+        let mut zone = square_zone().with_max_idle(3);
+        zone.update(&[track_at(1, 150.0, 150.0), track_at(2, 50.0, 50.0)], 1);
+        assert_eq!(zone.occupancy(), 1);
+        zone.update(&[] as &[STrack], 2);
+        let events = zone.update(&[] as &[STrack], 5);
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            (events[0].track_id, events[0].kind),
+            (1, ZoneEventKind::Exit)
+        );
+        assert_eq!((zone.total_entries(), zone.total_exits()), (1, 1));
+    }
+}
@@ -1,18 +1,52 @@
 //! Integration module for connecting object detection backends with ByteTrack.
 //!
 //! This module provides traits and utilities for integrating various inference
-//! backends (Burn, ONNX Runtime, etc.) with the ByteTrack tracker.
+//! backends (Burn, candle, tract ONNX, etc.) with the ByteTrack tracker.
 
 mod builder;
+mod decode;
 mod detector;
+mod image;
+mod multi_stream;
+mod nms;
+mod observer;
 mod pipeline;
+mod postprocess;
+mod preprocess;
+mod threaded;
 
 pub use builder::DetectionBuilder;
+pub use decode::{RawDetection, YOLOV5_ANCHORS, YoloDecoder, YoloVersion};
 pub use detector::{DetectionSource, IntoDetections};
-pub use pipeline::TrackerPipeline;
+pub use image::{ImageError, ImageView, OwnedImage, PixelFormat, PixelLayout};
+pub use multi_stream::{MultiStreamError, MultiStreamPipeline};
+pub use nms::{SoftNmsMethod, nms, nms_class_aware, nms_indices, soft_nms};
+pub use observer::{PipelineObserver, TrackLifecycle};
+pub use pipeline::{DetectionSchedule, TrackerPipeline};
+pub use postprocess::{PostprocessConfig, postprocess_detections};
+pub use preprocess::{
+    LetterboxInfo, PreprocessConfig, hwc_to_chw_normalized, letterbox, letterbox_info,
+    preprocess_image,
+};
+pub use threaded::{DropPolicy, FrameOutput, PipelineOptions, ThreadedPipeline};
+
+#[cfg(feature = "tokio")]
+pub use threaded::FrameStream;
 
 #[cfg(feature = "burn-backend")]
 mod burn_backend;
 
 #[cfg(feature = "burn-backend")]
-pub use burn_backend::{BurnDetector, BurnDetectorError, BurnModel, RawDetection};
+pub use burn_backend::{BurnDetector, BurnDetectorError, BurnModel};
+
+#[cfg(feature = "candle-backend")]
+mod candle_backend;
+
+#[cfg(feature = "candle-backend")]
+pub use candle_backend::{CandleDetector, CandleDetectorError, CandleModel};
+
+#[cfg(feature = "onnx-tract")]
+mod tract_backend;
+
+#[cfg(feature = "onnx-tract")]
+pub use tract_backend::{TractDetector, TractDetectorError};
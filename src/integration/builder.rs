@@ -1,6 +1,6 @@
 //! Builder for creating Detection objects from various input formats.
 
-use crate::tracker::Detection;
+use crate::tracker::{Detection, Keypoint, RleMask};
 
 /// Builder for creating `Detection` objects from various input formats.
 #[derive(Debug, Clone, Default)]
@@ -10,6 +10,9 @@ pub struct DetectionBuilder {
     x2: f32,
     y2: f32,
     score: f32,
+    class_id: Option<usize>,
+    mask: Option<RleMask>,
+    keypoints: Option<Vec<Keypoint>>,
 }
 
 impl DetectionBuilder {
@@ -51,9 +54,31 @@ impl DetectionBuilder {
         self
     }
 
+    /// Set the class ID.
+    pub fn class_id(mut self, class_id: usize) -> Self {
+        self.class_id = Some(class_id);
+        self
+    }
+
+    /// Set the instance segmentation mask.
+    pub fn mask(mut self, mask: RleMask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Set the pose keypoints.
+    pub fn keypoints(mut self, keypoints: Vec<Keypoint>) -> Self {
+        self.keypoints = Some(keypoints);
+        self
+    }
+
     /// Build the final `Detection`.
     pub fn build(self) -> Detection {
-        Detection::new(self.x1, self.y1, self.x2, self.y2, self.score)
+        let mut det = Detection::new(self.x1, self.y1, self.x2, self.y2, self.score);
+        det.class_id = self.class_id;
+        det.mask = self.mask;
+        det.keypoints = self.keypoints;
+        det
     }
 }
 
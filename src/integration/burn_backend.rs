@@ -22,7 +22,10 @@
 //! let detector = BurnDetector::new(model);
 //! ```
 
-use super::{DetectionBuilder, DetectionSource};
+use super::decode::RawDetection;
+use super::postprocess::{PostprocessConfig, postprocess_detections};
+use super::preprocess::{LetterboxInfo, PreprocessConfig, preprocess_image};
+use super::{DetectionSource, ImageError, ImageView};
 use crate::tracker::Detection;
 use burn::prelude::*;
 use burn::tensor::Tensor;
@@ -31,10 +34,13 @@ use burn::tensor::Tensor;
 #[derive(Debug, Clone)]
 pub enum BurnDetectorError {
     /// Input image has invalid dimensions.
+    #[deprecated(note = "no longer produced; malformed input is reported as `InvalidImage`")]
     InvalidInputDimensions {
         expected: (u32, u32, u32),
         got: (u32, u32, u32),
     },
+    /// Input image is malformed or can't be converted for the model.
+    InvalidImage(ImageError),
     /// Preprocessing failed.
     PreprocessingError(String),
     /// Model inference failed.
@@ -46,6 +52,7 @@ pub enum BurnDetectorError {
 impl std::fmt::Display for BurnDetectorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            #[allow(deprecated)]
             Self::InvalidInputDimensions { expected, got } => {
                 write!(
                     f,
@@ -53,6 +60,7 @@ impl std::fmt::Display for BurnDetectorError {
                     expected, got
                 )
             }
+            Self::InvalidImage(err) => write!(f, "Invalid image: {}", err),
             Self::PreprocessingError(msg) => write!(f, "Preprocessing error: {}", msg),
             Self::InferenceError(msg) => write!(f, "Inference error: {}", msg),
             Self::PostprocessingError(msg) => write!(f, "Postprocessing error: {}", msg),
@@ -62,17 +70,6 @@ impl std::fmt::Display for BurnDetectorError {
 
 impl std::error::Error for BurnDetectorError {}
 
-/// Raw detection output from the model before NMS.
-#[derive(Debug, Clone)]
-pub struct RawDetection {
-    /// Bounding box: [x1, y1, x2, y2] or [cx, cy, w, h] depending on model
-    pub bbox: [f32; 4],
-    /// Confidence score
-    pub score: f32,
-    /// Class ID (optional, for multi-class detection)
-    pub class_id: Option<usize>,
-}
-
 /// Trait for Burn-based detection models.
 ///
 /// Implement this trait for your specific model architecture.
@@ -86,6 +83,23 @@ pub trait BurnModel<B: Backend>: Send + Sync {
     /// Vector of raw detections before NMS filtering.
     fn forward(&self, input: Tensor<B, 4>) -> Vec<RawDetection>;
 
+    /// Run forward pass on a batched input tensor.
+    ///
+    /// # Arguments
+    /// * `input` - Input tensor of shape [batch, channels, height, width]
+    ///
+    /// # Returns
+    /// One vector of raw detections per batch item. The default implementation
+    /// splits the batch and calls `forward` on each item; override it to run
+    /// the whole batch in one pass.
+    fn forward_batch(&self, input: Tensor<B, 4>) -> Vec<Vec<RawDetection>> {
+        input
+            .split(1, 0)
+            .into_iter()
+            .map(|item| self.forward(item))
+            .collect()
+    }
+
     /// Get the expected input size (channels, height, width).
     fn input_size(&self) -> (u32, u32, u32) {
         (3, 640, 640) // Default YOLO input size
@@ -101,7 +115,8 @@ pub trait BurnModel<B: Backend>: Send + Sync {
 pub struct BurnDetector<B: Backend, M: BurnModel<B>> {
     model: M,
     device: B::Device,
-    conf_threshold: f32,
+    preprocess_config: PreprocessConfig,
+    postprocess_config: PostprocessConfig,
 }
 
 impl<B: Backend, M: BurnModel<B>> BurnDetector<B, M> {
@@ -110,91 +125,154 @@ impl<B: Backend, M: BurnModel<B>> BurnDetector<B, M> {
         Self {
             model,
             device,
-            conf_threshold: 0.25,
+            preprocess_config: PreprocessConfig::default(),
+            postprocess_config: PostprocessConfig::default(),
         }
     }
 
     /// Set the confidence threshold for filtering detections.
     pub fn with_conf_threshold(mut self, threshold: f32) -> Self {
-        self.conf_threshold = threshold;
+        self.postprocess_config.conf_threshold = threshold;
+        self
+    }
+
+    /// Set the IoU threshold for non-maximum suppression, or `None` to disable NMS.
+    pub fn with_nms_threshold(mut self, threshold: Option<f32>) -> Self {
+        self.postprocess_config.nms_threshold = threshold;
         self
     }
 
-    /// Preprocess raw image bytes to a Burn tensor.
+    /// Suppress overlapping boxes across classes instead of per class.
+    pub fn with_class_agnostic_nms(mut self, agnostic: bool) -> Self {
+        self.postprocess_config.class_agnostic_nms = agnostic;
+        self
+    }
+
+    /// Set the color used to pad letterboxed frames.
+    pub fn with_pad_color(mut self, color: [u8; 3]) -> Self {
+        self.preprocess_config.pad_color = color;
+        self
+    }
+
+    /// Set the per-channel mean and standard deviation used for normalization.
+    ///
+    /// Pixels are scaled to `[0, 1]` before `(x - mean) / std` is applied.
+    pub fn with_normalization(mut self, mean: [f32; 3], std: [f32; 3]) -> Self {
+        self.preprocess_config.mean = mean;
+        self.preprocess_config.std = std;
+        self
+    }
+
+    /// Preprocess an image to a Burn tensor.
     ///
-    /// Override this method for custom preprocessing.
+    /// Frames that don't match the model input size are letterboxed with
+    /// bilinear interpolation. The returned `LetterboxInfo` maps model-space
+    /// boxes back to the original image.
     pub fn preprocess(
         &self,
-        input: &[u8],
-        width: u32,
-        height: u32,
-    ) -> Result<Tensor<B, 4>, BurnDetectorError> {
-        let (channels, target_h, target_w) = self.model.input_size();
-        let expected_len = (width * height * channels) as usize;
+        image: &ImageView<'_>,
+    ) -> Result<(Tensor<B, 4>, LetterboxInfo), BurnDetectorError> {
+        let (data, letterbox) = self.preprocess_data(image)?;
+        Ok((self.to_tensor(data, 1), letterbox))
+    }
 
-        if input.len() != expected_len {
-            return Err(BurnDetectorError::InvalidInputDimensions {
-                expected: (channels, height, width),
-                got: (channels, height, input.len() as u32 / (height * channels)),
-            });
+    /// Preprocess a batch of frames into a single `[N, C, H, W]` tensor.
+    ///
+    /// Images may have different sizes and pixel formats.
+    pub fn preprocess_batch(
+        &self,
+        images: &[ImageView<'_>],
+    ) -> Result<(Tensor<B, 4>, Vec<LetterboxInfo>), BurnDetectorError> {
+        let mut batch = Vec::new();
+        let mut letterboxes = Vec::with_capacity(images.len());
+        for image in images {
+            let (data, letterbox) = self.preprocess_data(image)?;
+            batch.extend(data);
+            letterboxes.push(letterbox);
         }
+        Ok((self.to_tensor(batch, images.len()), letterboxes))
+    }
 
-        // Convert u8 to f32 and normalize to [0, 1]
-        let data: Vec<f32> = input.iter().map(|&x| x as f32 / 255.0).collect();
+    /// Convert and letterbox a single image into planar `f32` data.
+    fn preprocess_data(
+        &self,
+        image: &ImageView<'_>,
+    ) -> Result<(Vec<f32>, LetterboxInfo), BurnDetectorError> {
+        let (channels, target_h, target_w) = self.model.input_size();
 
-        // Create tensor [C, H, W] then reshape to [1, C, H, W]
-        let tensor = Tensor::<B, 1>::from_floats(data.as_slice(), &self.device).reshape([
-            1,
-            channels as usize,
-            height as usize,
-            width as usize,
-        ]);
-
-        // Resize if needed (simplified - real impl would use interpolation)
-        if height != target_h || width != target_w {
-            // For now, we expect input to match model size
-            // Real implementation would resize here
-            return Err(BurnDetectorError::PreprocessingError(format!(
-                "Input size {}x{} doesn't match model size {}x{}. Resize not implemented.",
-                width, height, target_w, target_h
-            )));
-        }
+        let pixels = image
+            .to_packed(channels)
+            .map_err(BurnDetectorError::InvalidImage)?;
 
-        Ok(tensor)
+        Ok(preprocess_image(
+            &pixels,
+            image.width(),
+            image.height(),
+            channels,
+            target_w,
+            target_h,
+            &self.preprocess_config,
+        ))
     }
 
-    /// Convert raw model outputs to Detection objects.
-    fn postprocess(&self, raw_detections: Vec<RawDetection>) -> Vec<Detection> {
-        raw_detections
-            .into_iter()
-            .filter(|d| d.score >= self.conf_threshold)
-            .map(|d| {
-                let builder = DetectionBuilder::new().score(d.score);
-                if self.model.bbox_is_xywh() {
-                    builder
-                        .xywh(d.bbox[0], d.bbox[1], d.bbox[2], d.bbox[3])
-                        .build()
-                } else {
-                    builder
-                        .tlbr(d.bbox[0], d.bbox[1], d.bbox[2], d.bbox[3])
-                        .build()
-                }
-            })
-            .collect()
+    /// Build a `[batch, C, H, W]` tensor from planar data.
+    fn to_tensor(&self, data: Vec<f32>, batch: usize) -> Tensor<B, 4> {
+        let (channels, target_h, target_w) = self.model.input_size();
+        Tensor::<B, 1>::from_floats(data.as_slice(), &self.device).reshape([
+            batch,
+            channels as usize,
+            target_h as usize,
+            target_w as usize,
+        ])
+    }
+
+    /// Convert raw model outputs to NMS-filtered Detection objects in original
+    /// image coordinates.
+    fn postprocess(
+        &self,
+        raw_detections: Vec<RawDetection>,
+        letterbox: &LetterboxInfo,
+    ) -> Vec<Detection> {
+        postprocess_detections(
+            raw_detections,
+            self.model.bbox_is_xywh(),
+            letterbox,
+            &self.postprocess_config,
+        )
     }
 }
 
 impl<B: Backend, M: BurnModel<B>> DetectionSource for BurnDetector<B, M> {
     type Error = BurnDetectorError;
 
-    fn detect(
-        &mut self,
-        input: &[u8],
-        width: u32,
-        height: u32,
-    ) -> Result<Vec<Detection>, Self::Error> {
-        let tensor = self.preprocess(input, width, height)?;
+    fn detect_image(&mut self, image: &ImageView<'_>) -> Result<Vec<Detection>, Self::Error> {
+        let (tensor, letterbox) = self.preprocess(image)?;
         let raw_detections = self.model.forward(tensor);
-        Ok(self.postprocess(raw_detections))
+        Ok(self.postprocess(raw_detections, &letterbox))
+    }
+
+    fn detect_batch(
+        &mut self,
+        images: &[ImageView<'_>],
+    ) -> Result<Vec<Vec<Detection>>, Self::Error> {
+        if images.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (tensor, letterboxes) = self.preprocess_batch(images)?;
+        let raw_batch = self.model.forward_batch(tensor);
+        if raw_batch.len() != images.len() {
+            return Err(BurnDetectorError::InferenceError(format!(
+                "Model returned {} outputs for a batch of {}",
+                raw_batch.len(),
+                images.len()
+            )));
+        }
+
+        Ok(raw_batch
+            .into_iter()
+            .zip(&letterboxes)
+            .map(|(raw, letterbox)| self.postprocess(raw, letterbox))
+            .collect())
     }
 }
@@ -0,0 +1,268 @@
+//! Candle inference backend for object detection.
+//!
+//! This module provides a `CandleDetector` that implements `DetectionSource`
+//! for running object detection models built with Hugging Face candle.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use bytetrack_rs::{CandleDetector, CandleModel, RawDetection};
+//! use candle_core::{Device, Tensor};
+//!
+//! // Implement CandleModel for your detection model
+//! struct MyYoloModel { /* ... */ }
+//!
+//! impl CandleModel for MyYoloModel {
+//!     fn forward(&self, input: &Tensor) -> candle_core::Result<Vec<RawDetection>> {
+//!         // Run inference
+//!     }
+//! }
+//!
+//! let model = MyYoloModel::load("model.safetensors");
+//! let detector = CandleDetector::new(model, Device::Cpu);
+//! ```
+
+use candle_core::{Device, Tensor};
+
+use super::decode::RawDetection;
+use super::postprocess::{PostprocessConfig, postprocess_detections};
+use super::preprocess::{LetterboxInfo, PreprocessConfig, preprocess_image};
+use super::{DetectionSource, ImageError, ImageView};
+use crate::tracker::Detection;
+
+/// Error type for candle detection failures.
+#[derive(Debug, Clone)]
+pub enum CandleDetectorError {
+    /// Input image is malformed or can't be converted for the model.
+    InvalidImage(ImageError),
+    /// Preprocessing failed.
+    PreprocessingError(String),
+    /// Model inference failed.
+    InferenceError(String),
+}
+
+impl std::fmt::Display for CandleDetectorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidImage(err) => write!(f, "Invalid image: {}", err),
+            Self::PreprocessingError(msg) => write!(f, "Preprocessing error: {}", msg),
+            Self::InferenceError(msg) => write!(f, "Inference error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CandleDetectorError {}
+
+/// Trait for candle-based detection models.
+///
+/// Implement this trait for your specific model architecture. It mirrors
+/// `BurnModel`, so models can be swapped without touching `TrackerPipeline`.
+pub trait CandleModel: Send + Sync {
+    /// Run forward pass on the input tensor.
+    ///
+    /// # Arguments
+    /// * `input` - Input tensor of shape [batch, channels, height, width]
+    ///
+    /// # Returns
+    /// Vector of raw detections before NMS filtering.
+    fn forward(&self, input: &Tensor) -> candle_core::Result<Vec<RawDetection>>;
+
+    /// Run forward pass on a batched input tensor.
+    ///
+    /// # Returns
+    /// One vector of raw detections per batch item. The default implementation
+    /// splits the batch and calls `forward` on each item; override it to run
+    /// the whole batch in one pass.
+    fn forward_batch(&self, input: &Tensor) -> candle_core::Result<Vec<Vec<RawDetection>>> {
+        (0..input.dim(0)?)
+            .map(|i| self.forward(&input.narrow(0, i, 1)?))
+            .collect()
+    }
+
+    /// Get the expected input size (channels, height, width).
+    fn input_size(&self) -> (u32, u32, u32) {
+        (3, 640, 640) // Default YOLO input size
+    }
+
+    /// Whether bbox output is in XYWH format (vs TLBR).
+    fn bbox_is_xywh(&self) -> bool {
+        true // Most YOLO variants use XYWH
+    }
+}
+
+/// Candle-based object detector implementing `DetectionSource`.
+pub struct CandleDetector<M: CandleModel> {
+    model: M,
+    device: Device,
+    preprocess_config: PreprocessConfig,
+    postprocess_config: PostprocessConfig,
+}
+
+impl<M: CandleModel> CandleDetector<M> {
+    /// Create a new candle detector with the given model and device.
+    pub fn new(model: M, device: Device) -> Self {
+        Self {
+            model,
+            device,
+            preprocess_config: PreprocessConfig::default(),
+            postprocess_config: PostprocessConfig::default(),
+        }
+    }
+
+    /// Set the confidence threshold for filtering detections.
+    pub fn with_conf_threshold(mut self, threshold: f32) -> Self {
+        self.postprocess_config.conf_threshold = threshold;
+        self
+    }
+
+    /// Set the IoU threshold for non-maximum suppression, or `None` to disable NMS.
+    pub fn with_nms_threshold(mut self, threshold: Option<f32>) -> Self {
+        self.postprocess_config.nms_threshold = threshold;
+        self
+    }
+
+    /// Suppress overlapping boxes across classes instead of per class.
+    pub fn with_class_agnostic_nms(mut self, agnostic: bool) -> Self {
+        self.postprocess_config.class_agnostic_nms = agnostic;
+        self
+    }
+
+    /// Set the color used to pad letterboxed frames.
+    pub fn with_pad_color(mut self, color: [u8; 3]) -> Self {
+        self.preprocess_config.pad_color = color;
+        self
+    }
+
+    /// Set the per-channel mean and standard deviation used for normalization.
+    ///
+    /// Pixels are scaled to `[0, 1]` before `(x - mean) / std` is applied.
+    pub fn with_normalization(mut self, mean: [f32; 3], std: [f32; 3]) -> Self {
+        self.preprocess_config.mean = mean;
+        self.preprocess_config.std = std;
+        self
+    }
+
+    /// Get a reference to the underlying model.
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+
+    /// Preprocess an image to a candle tensor.
+    ///
+    /// Frames that don't match the model input size are letterboxed with
+    /// bilinear interpolation. The returned `LetterboxInfo` maps model-space
+    /// boxes back to the original image.
+    pub fn preprocess(
+        &self,
+        image: &ImageView<'_>,
+    ) -> Result<(Tensor, LetterboxInfo), CandleDetectorError> {
+        let (data, letterbox) = self.preprocess_data(image)?;
+        Ok((self.to_tensor(data, 1)?, letterbox))
+    }
+
+    /// Preprocess a batch of frames into a single `[N, C, H, W]` tensor.
+    ///
+    /// Images may have different sizes and pixel formats.
+    pub fn preprocess_batch(
+        &self,
+        images: &[ImageView<'_>],
+    ) -> Result<(Tensor, Vec<LetterboxInfo>), CandleDetectorError> {
+        let mut batch = Vec::new();
+        let mut letterboxes = Vec::with_capacity(images.len());
+        for image in images {
+            let (data, letterbox) = self.preprocess_data(image)?;
+            batch.extend(data);
+            letterboxes.push(letterbox);
+        }
+        Ok((self.to_tensor(batch, images.len())?, letterboxes))
+    }
+
+    /// Convert and letterbox a single image into planar `f32` data.
+    fn preprocess_data(
+        &self,
+        image: &ImageView<'_>,
+    ) -> Result<(Vec<f32>, LetterboxInfo), CandleDetectorError> {
+        let (channels, target_h, target_w) = self.model.input_size();
+
+        let pixels = image
+            .to_packed(channels)
+            .map_err(CandleDetectorError::InvalidImage)?;
+
+        Ok(preprocess_image(
+            &pixels,
+            image.width(),
+            image.height(),
+            channels,
+            target_w,
+            target_h,
+            &self.preprocess_config,
+        ))
+    }
+
+    /// Build a `[batch, C, H, W]` tensor from planar data.
+    fn to_tensor(&self, data: Vec<f32>, batch: usize) -> Result<Tensor, CandleDetectorError> {
+        let (channels, target_h, target_w) = self.model.input_size();
+        let shape = (
+            batch,
+            channels as usize,
+            target_h as usize,
+            target_w as usize,
+        );
+        Tensor::from_vec(data, shape, &self.device)
+            .map_err(|e| CandleDetectorError::PreprocessingError(e.to_string()))
+    }
+
+    fn postprocess(
+        &self,
+        raw_detections: Vec<RawDetection>,
+        letterbox: &LetterboxInfo,
+    ) -> Vec<Detection> {
+        postprocess_detections(
+            raw_detections,
+            self.model.bbox_is_xywh(),
+            letterbox,
+            &self.postprocess_config,
+        )
+    }
+}
+
+impl<M: CandleModel> DetectionSource for CandleDetector<M> {
+    type Error = CandleDetectorError;
+
+    fn detect_image(&mut self, image: &ImageView<'_>) -> Result<Vec<Detection>, Self::Error> {
+        let (tensor, letterbox) = self.preprocess(image)?;
+        let raw_detections = self
+            .model
+            .forward(&tensor)
+            .map_err(|e| CandleDetectorError::InferenceError(e.to_string()))?;
+        Ok(self.postprocess(raw_detections, &letterbox))
+    }
+
+    fn detect_batch(
+        &mut self,
+        images: &[ImageView<'_>],
+    ) -> Result<Vec<Vec<Detection>>, Self::Error> {
+        if images.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (tensor, letterboxes) = self.preprocess_batch(images)?;
+        let raw_batch = self
+            .model
+            .forward_batch(&tensor)
+            .map_err(|e| CandleDetectorError::InferenceError(e.to_string()))?;
+        if raw_batch.len() != images.len() {
+            return Err(CandleDetectorError::InferenceError(format!(
+                "Model returned {} outputs for a batch of {}",
+                raw_batch.len(),
+                images.len()
+            )));
+        }
+
+        Ok(raw_batch
+            .into_iter()
+            .zip(&letterboxes)
+            .map(|(raw, letterbox)| self.postprocess(raw, letterbox))
+            .collect())
+    }
+}
@@ -0,0 +1,305 @@
+//! Decoders for raw YOLO-family output tensors.
+//!
+//! These turn the flat `f32` output of a detection head into `RawDetection`s
+//! in model input pixel coordinates (XYWH), ready for NMS and un-letterboxing.
+
+/// Raw detection output from the model before NMS.
+#[derive(Debug, Clone)]
+pub struct RawDetection {
+    /// Bounding box: [x1, y1, x2, y2] or [cx, cy, w, h] depending on model
+    pub bbox: [f32; 4],
+    /// Confidence score
+    pub score: f32,
+    /// Class ID (optional, for multi-class detection)
+    pub class_id: Option<usize>,
+}
+
+/// Default YOLOv5 anchors (P3/8, P4/16, P5/32) in input pixels.
+pub const YOLOV5_ANCHORS: [[[f32; 2]; 3]; 3] = [
+    [[10.0, 13.0], [16.0, 30.0], [33.0, 23.0]],
+    [[30.0, 61.0], [62.0, 45.0], [59.0, 119.0]],
+    [[116.0, 90.0], [156.0, 198.0], [373.0, 326.0]],
+];
+
+/// Output layout of a YOLO detection head.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YoloVersion {
+    /// YOLOv5: `[N, 5 + C]` rows of `cx, cy, w, h, obj, cls...`.
+    ///
+    /// If anchors are configured the rows are treated as raw head logits laid
+    /// out per level as `[anchor, y, x]` and decoded with the YOLOv5 grid formula.
+    V5,
+    /// YOLOv8: `[4 + C, N]` channel-major layout of `cx, cy, w, h, cls...`, no objectness.
+    V8,
+    /// YOLOX: `[N, 5 + C]` rows of grid-relative `x, y, log w, log h, obj, cls...`
+    /// laid out per stride level as `[y, x]`.
+    X,
+}
+
+/// Decoder turning YOLO head outputs into `RawDetection`s.
+#[derive(Debug, Clone)]
+pub struct YoloDecoder {
+    version: YoloVersion,
+    num_classes: usize,
+    input_width: u32,
+    input_height: u32,
+    strides: Vec<u32>,
+    anchors: Option<Vec<Vec<[f32; 2]>>>,
+    conf_threshold: f32,
+}
+
+impl YoloDecoder {
+    /// Create a decoder for the given head layout and number of classes.
+    pub fn new(version: YoloVersion, num_classes: usize) -> Self {
+        Self {
+            version,
+            num_classes,
+            input_width: 640,
+            input_height: 640,
+            strides: vec![8, 16, 32],
+            anchors: None,
+            conf_threshold: 0.0,
+        }
+    }
+
+    /// Set the model input size used to build the decoding grid.
+    pub fn with_input_size(mut self, width: u32, height: u32) -> Self {
+        self.input_width = width;
+        self.input_height = height;
+        self
+    }
+
+    /// Set the feature map strides, one per output level.
+    ///
+    /// # Panics
+    /// Panics if any stride is zero.
+    pub fn with_strides(mut self, strides: Vec<u32>) -> Self {
+        assert!(
+            strides.iter().all(|&s| s > 0),
+            "YOLO strides must be non-zero, got {:?}",
+            strides
+        );
+        self.strides = strides;
+        self
+    }
+
+    /// Set per-level anchors, enabling raw YOLOv5 grid/anchor decoding.
+    pub fn with_anchors(mut self, anchors: Vec<Vec<[f32; 2]>>) -> Self {
+        self.anchors = Some(anchors);
+        self
+    }
+
+    /// Use the default YOLOv5 anchors for raw head decoding.
+    pub fn with_default_anchors(self) -> Self {
+        self.with_anchors(YOLOV5_ANCHORS.iter().map(|l| l.to_vec()).collect())
+    }
+
+    /// Discard candidates scoring below this threshold while decoding.
+    pub fn with_conf_threshold(mut self, threshold: f32) -> Self {
+        self.conf_threshold = threshold;
+        self
+    }
+
+    /// Number of values per candidate in row-major layouts.
+    fn row_len(&self) -> usize {
+        match self.version {
+            YoloVersion::V5 | YoloVersion::X => 5 + self.num_classes,
+            YoloVersion::V8 => 4 + self.num_classes,
+        }
+    }
+
+    /// Decode a flat output tensor into candidates with XYWH boxes.
+    ///
+    /// Trailing values that don't form a full candidate are ignored.
+    pub fn decode(&self, output: &[f32]) -> Vec<RawDetection> {
+        match self.version {
+            YoloVersion::V5 if self.anchors.is_some() => self.decode_v5_raw(output),
+            YoloVersion::V5 => self.decode_v5(output),
+            YoloVersion::V8 => self.decode_v8(output),
+            YoloVersion::X => self.decode_x(output),
+        }
+    }
+
+    /// Best class index and its score.
+    fn best_class(&self, class_scores: &[f32]) -> (usize, f32) {
+        class_scores
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap_or((0, 1.0))
+    }
+
+    fn candidate(&self, bbox: [f32; 4], obj: f32, class_scores: &[f32]) -> Option<RawDetection> {
+        let (class_id, class_score) = self.best_class(class_scores);
+        let score = obj * class_score;
+        (score >= self.conf_threshold).then_some(RawDetection {
+            bbox,
+            score,
+            class_id: Some(class_id),
+        })
+    }
+
+    fn decode_v5(&self, output: &[f32]) -> Vec<RawDetection> {
+        output
+            .chunks_exact(self.row_len())
+            .filter_map(|row| self.candidate([row[0], row[1], row[2], row[3]], row[4], &row[5..]))
+            .collect()
+    }
+
+    fn decode_v5_raw(&self, output: &[f32]) -> Vec<RawDetection> {
+        let anchors = self.anchors.as_deref().unwrap_or_default();
+        let row_len = self.row_len();
+        let mut rows = output.chunks_exact(row_len);
+        let mut detections = Vec::new();
+
+        for (&stride, level_anchors) in self.strides.iter().zip(anchors) {
+            let (nx, ny) = (self.input_width / stride, self.input_height / stride);
+            for anchor in level_anchors {
+                for gy in 0..ny {
+                    for gx in 0..nx {
+                        let Some(row) = rows.next() else {
+                            return detections;
+                        };
+                        let s: Vec<f32> = row.iter().map(|&v| sigmoid(v)).collect();
+                        let cx = (s[0] * 2.0 - 0.5 + gx as f32) * stride as f32;
+                        let cy = (s[1] * 2.0 - 0.5 + gy as f32) * stride as f32;
+                        let w = (s[2] * 2.0).powi(2) * anchor[0];
+                        let h = (s[3] * 2.0).powi(2) * anchor[1];
+                        detections.extend(self.candidate([cx, cy, w, h], s[4], &s[5..]));
+                    }
+                }
+            }
+        }
+        detections
+    }
+
+    fn decode_v8(&self, output: &[f32]) -> Vec<RawDetection> {
+        let channels = self.row_len();
+        let n = output.len() / channels;
+        let at = |c: usize, i: usize| output[c * n + i];
+
+        (0..n)
+            .filter_map(|i| {
+                let classes: Vec<f32> = (4..channels).map(|c| at(c, i)).collect();
+                self.candidate([at(0, i), at(1, i), at(2, i), at(3, i)], 1.0, &classes)
+            })
+            .collect()
+    }
+
+    fn decode_x(&self, output: &[f32]) -> Vec<RawDetection> {
+        let mut rows = output.chunks_exact(self.row_len());
+        let mut detections = Vec::new();
+
+        for &stride in &self.strides {
+            let (nx, ny) = (self.input_width / stride, self.input_height / stride);
+            for gy in 0..ny {
+                for gx in 0..nx {
+                    let Some(row) = rows.next() else {
+                        return detections;
+                    };
+                    let s = stride as f32;
+                    let cx = (row[0] + gx as f32) * s;
+                    let cy = (row[1] + gy as f32) * s;
+                    let w = row[2].exp() * s;
+                    let h = row[3].exp() * s;
+                    detections.extend(self.candidate([cx, cy, w, h], row[4], &row[5..]));
+                }
+            }
+        }
+        detections
+    }
+}
+
+#[inline]
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_v5_exported() {
+        let output = [
+            100.0, 100.0, 20.0, 40.0, 0.9, 0.1, 0.8, // class 1, score 0.72
+            50.0, 50.0, 10.0, 10.0, 0.1, 0.9, 0.1, // score 0.09
+        ];
+        let dets = YoloDecoder::new(YoloVersion::V5, 2)
+            .with_conf_threshold(0.25)
+            .decode(&output);
+        assert_eq!(dets.len(), 1);
+        assert_eq!(dets[0].class_id, Some(1));
+        assert!((dets[0].score - 0.72).abs() < 1e-6);
+        assert_eq!(dets[0].bbox, [100.0, 100.0, 20.0, 40.0]);
+    }
+
+    #[test]
+    fn test_decode_v5_raw_grid() {
+        // Single 2x2 level with stride 8 and one anchor; all logits zero.
+        let output = vec![0.0f32; 4 * 6];
+        let dets = YoloDecoder::new(YoloVersion::V5, 1)
+            .with_input_size(16, 16)
+            .with_strides(vec![8])
+            .with_anchors(vec![vec![[10.0, 20.0]]])
+            .decode(&output);
+        assert_eq!(dets.len(), 4);
+        // sigmoid(0) = 0.5 -> center offset 0.5, wh = anchor.
+        assert_eq!(dets[0].bbox, [4.0, 4.0, 10.0, 20.0]);
+        assert_eq!(dets[3].bbox, [12.0, 12.0, 10.0, 20.0]);
+        assert!((dets[0].score - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_decode_v8_channel_major() {
+        // Two candidates, two classes: layout [cx.., cy.., w.., h.., c0.., c1..]
+        let output = [
+            10.0, 30.0, // cx
+            20.0, 40.0, // cy
+            5.0, 6.0, // w
+            7.0, 8.0, // h
+            0.1, 0.6, // class 0
+            0.7, 0.2, // class 1
+        ];
+        let dets = YoloDecoder::new(YoloVersion::V8, 2).decode(&output);
+        assert_eq!(dets.len(), 2);
+        assert_eq!(dets[0].bbox, [10.0, 20.0, 5.0, 7.0]);
+        assert_eq!(dets[0].class_id, Some(1));
+        assert_eq!(dets[1].class_id, Some(0));
+        assert!((dets[1].score - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_decode_x_grid() {
+        // One 2x1 level with stride 16.
+        let output = [
+            0.5,
+            0.5,
+            0.0,
+            0.0,
+            1.0,
+            0.9, //
+            0.5,
+            0.5,
+            1.0_f32.ln(),
+            2.0_f32.ln(),
+            0.5,
+            0.5,
+        ];
+        let dets = YoloDecoder::new(YoloVersion::X, 1)
+            .with_input_size(32, 16)
+            .with_strides(vec![16])
+            .decode(&output);
+        assert_eq!(dets.len(), 2);
+        assert_eq!(dets[0].bbox, [8.0, 8.0, 16.0, 16.0]);
+        assert_eq!(dets[1].bbox, [24.0, 8.0, 16.0, 32.0]);
+        assert!((dets[1].score - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero")]
+    fn test_zero_stride_rejected() {
+        let _ = YoloDecoder::new(YoloVersion::X, 1).with_strides(vec![8, 0]);
+    }
+}
@@ -1,5 +1,6 @@
 //! Trait for object detection inference backends.
 
+use super::ImageView;
 use crate::tracker::Detection;
 
 /// Trait for object detection inference backends.
@@ -9,7 +10,7 @@ use crate::tracker::Detection;
 /// # Example
 ///
 /// ```ignore
-/// use bytetrack_rs::{DetectionSource, Detection};
+/// use bytetrack_rs::{DetectionSource, Detection, ImageView};
 ///
 /// struct MyDetector {
 ///     // Your model here
@@ -18,7 +19,7 @@ use crate::tracker::Detection;
 /// impl DetectionSource for MyDetector {
 ///     type Error = std::io::Error;
 ///
-///     fn detect(&mut self, input: &[u8], width: u32, height: u32) -> Result<Vec<Detection>, Self::Error> {
+///     fn detect_image(&mut self, image: &ImageView<'_>) -> Result<Vec<Detection>, Self::Error> {
 ///         // Run inference and return detections
 ///         Ok(vec![])
 ///     }
@@ -28,21 +29,50 @@ pub trait DetectionSource {
     /// Error type for detection failures.
     type Error;
 
-    /// Run inference on raw image data and return detections.
+    /// Run inference on an image and return detections.
     ///
     /// # Arguments
-    /// * `input` - Raw image bytes (format depends on implementation)
-    /// * `width` - Image width in pixels
-    /// * `height` - Image height in pixels
+    /// * `image` - Image with explicit pixel format, layout and row stride
     ///
     /// # Returns
     /// A vector of `Detection` objects, or an error.
+    fn detect_image(&mut self, image: &ImageView<'_>) -> Result<Vec<Detection>, Self::Error>;
+
+    /// Run inference on raw image data and return detections.
+    ///
+    /// Compatibility shim for `detect_image`: `input` is interpreted as a
+    /// tightly packed, interleaved RGB image.
+    ///
+    /// # Arguments
+    /// * `input` - Raw RGB image bytes
+    /// * `width` - Image width in pixels
+    /// * `height` - Image height in pixels
     fn detect(
         &mut self,
         input: &[u8],
         width: u32,
         height: u32,
-    ) -> Result<Vec<Detection>, Self::Error>;
+    ) -> Result<Vec<Detection>, Self::Error> {
+        self.detect_image(&ImageView::rgb(input, width, height))
+    }
+
+    /// Run inference on a batch of images and return detections per image.
+    ///
+    /// The default implementation calls `detect_image` once per image; backends
+    /// that support batched inference should override it to run a single
+    /// forward pass.
+    ///
+    /// # Returns
+    /// One vector of detections per input image, in the same order.
+    fn detect_batch(
+        &mut self,
+        images: &[ImageView<'_>],
+    ) -> Result<Vec<Vec<Detection>>, Self::Error> {
+        images
+            .iter()
+            .map(|image| self.detect_image(image))
+            .collect()
+    }
 }
 
 /// Helper trait for converting model-specific outputs to `Detection`.
@@ -58,3 +88,39 @@ impl IntoDetections for Vec<Detection> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::{ImageError, PixelFormat};
+
+    /// Records the packed RGB bytes it was given.
+    struct RecordingDetector {
+        input: Vec<u8>,
+    }
+
+    impl DetectionSource for RecordingDetector {
+        type Error = ImageError;
+
+        fn detect_image(&mut self, image: &ImageView<'_>) -> Result<Vec<Detection>, Self::Error> {
+            assert_eq!(image.format(), PixelFormat::Rgb8);
+            self.input = image.to_packed(3)?.into_owned();
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn test_detect_reads_packed_rgb() {
+        let mut detector = RecordingDetector { input: vec![] };
+        detector.detect(&[1, 2, 3, 4, 5, 6], 2, 1).unwrap();
+        assert_eq!(detector.input, [1, 2, 3, 4, 5, 6]);
+
+        assert!(matches!(
+            detector.detect(&[1, 2, 3], 2, 1),
+            Err(ImageError::BufferTooSmall {
+                expected: 6,
+                got: 3
+            })
+        ));
+    }
+}
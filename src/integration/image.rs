@@ -0,0 +1,502 @@
+//! Typed, borrowed image input for detection backends.
+//!
+//! `ImageView` describes how pixels are laid out in a byte buffer (pixel
+//! format, channel layout and row stride) so that backends don't have to guess,
+//! and provides conversions to the packed RGB or grayscale buffers models expect.
+
+use std::borrow::Cow;
+
+/// Pixel format of an image buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 8-bit red, green, blue.
+    Rgb8,
+    /// 8-bit blue, green, red (OpenCV default).
+    Bgr8,
+    /// 8-bit red, green, blue, alpha.
+    Rgba8,
+    /// 8-bit blue, green, red, alpha.
+    Bgra8,
+    /// 8-bit single-channel luminance.
+    Gray8,
+    /// YUV 4:2:0 with a full-resolution Y plane followed by an interleaved UV plane.
+    Nv12,
+    /// YUV 4:2:0 with separate Y, U and V planes (I420).
+    Yuv420p,
+}
+
+impl PixelFormat {
+    /// Number of interleaved channels, or 1 for planar YUV formats.
+    pub fn channels(&self) -> usize {
+        match self {
+            Self::Rgb8 | Self::Bgr8 => 3,
+            Self::Rgba8 | Self::Bgra8 => 4,
+            Self::Gray8 | Self::Nv12 | Self::Yuv420p => 1,
+        }
+    }
+
+    /// Indices of the red, green and blue channels for RGB-family formats.
+    fn rgb_indices(&self) -> Option<[usize; 3]> {
+        match self {
+            Self::Rgb8 | Self::Rgba8 => Some([0, 1, 2]),
+            Self::Bgr8 | Self::Bgra8 => Some([2, 1, 0]),
+            _ => None,
+        }
+    }
+}
+
+/// Memory layout of the channels of an RGB-family image.
+///
+/// Ignored for grayscale and YUV formats, whose layout is fixed by the format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelLayout {
+    /// Interleaved channels: `[row][col][channel]`.
+    #[default]
+    Hwc,
+    /// Planar channels: `[channel][row][col]`, each plane `stride * height` bytes.
+    Chw,
+}
+
+/// Error type for invalid image buffers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageError {
+    /// Width or height is zero.
+    Empty,
+    /// Row stride is smaller than the bytes needed for one row.
+    InvalidStride { min: usize, got: usize },
+    /// The buffer is smaller than the format, size and stride require.
+    BufferTooSmall { expected: usize, got: usize },
+    /// The requested channel count can't be produced from this image.
+    UnsupportedChannels(u32),
+}
+
+impl std::fmt::Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Image has zero width or height"),
+            Self::InvalidStride { min, got } => {
+                write!(f, "Invalid row stride: need at least {}, got {}", min, got)
+            }
+            Self::BufferTooSmall { expected, got } => {
+                write!(
+                    f,
+                    "Image buffer too small: expected {} bytes, got {}",
+                    expected, got
+                )
+            }
+            Self::UnsupportedChannels(c) => write!(f, "Unsupported channel count: {}", c),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+/// A borrowed image with an explicit pixel format, layout and row stride.
+#[derive(Debug, Clone, Copy)]
+pub struct ImageView<'a> {
+    data: &'a [u8],
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    layout: PixelLayout,
+    stride: usize,
+}
+
+impl<'a> ImageView<'a> {
+    /// Create a view over a tightly packed, interleaved image.
+    pub fn new(data: &'a [u8], width: u32, height: u32, format: PixelFormat) -> Self {
+        Self {
+            data,
+            width,
+            height,
+            format,
+            layout: PixelLayout::Hwc,
+            stride: Self::min_stride(width, format, PixelLayout::Hwc),
+        }
+    }
+
+    /// Create a view over a tightly packed RGB image.
+    pub fn rgb(data: &'a [u8], width: u32, height: u32) -> Self {
+        Self::new(data, width, height, PixelFormat::Rgb8)
+    }
+
+    /// Create a view over a tightly packed BGR image.
+    pub fn bgr(data: &'a [u8], width: u32, height: u32) -> Self {
+        Self::new(data, width, height, PixelFormat::Bgr8)
+    }
+
+    /// Set the channel layout. The stride is reset to the tight stride for that layout.
+    pub fn with_layout(mut self, layout: PixelLayout) -> Self {
+        self.layout = layout;
+        self.stride = Self::min_stride(self.width, self.format, layout);
+        self
+    }
+
+    /// Set the number of bytes between the starts of consecutive rows.
+    ///
+    /// For planar layouts this is the stride of each plane (of the luma plane
+    /// for YUV formats; chroma planes of `Yuv420p` use half of it, rounded up).
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    fn min_stride(width: u32, format: PixelFormat, layout: PixelLayout) -> usize {
+        match (format.rgb_indices(), layout) {
+            (Some(_), PixelLayout::Hwc) => width as usize * format.channels(),
+            _ => width as usize,
+        }
+    }
+
+    /// Get the raw pixel data.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Get the image width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the image height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get the pixel format.
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Get the channel layout.
+    pub fn layout(&self) -> PixelLayout {
+        self.layout
+    }
+
+    /// Get the row stride in bytes.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Number of bytes the buffer must hold for this format, size and stride.
+    fn required_len(&self) -> usize {
+        let (w, h, s) = (self.width as usize, self.height as usize, self.stride);
+        let last_row = |row_bytes: usize, rows: usize| s * (rows - 1) + row_bytes;
+        match self.format {
+            PixelFormat::Nv12 => s * h + last_row(w.div_ceil(2) * 2, h.div_ceil(2)),
+            PixelFormat::Yuv420p => {
+                let cs = s.div_ceil(2);
+                let ch = h.div_ceil(2);
+                s * h + cs * ch + cs * (ch - 1) + w.div_ceil(2)
+            }
+            _ if self.layout == PixelLayout::Chw && self.format.rgb_indices().is_some() => {
+                s * h * (self.format.channels() - 1) + last_row(w, h)
+            }
+            _ => last_row(Self::min_stride(self.width, self.format, self.layout), h),
+        }
+    }
+
+    /// Check that the buffer is large enough for the described image.
+    pub fn validate(&self) -> Result<(), ImageError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(ImageError::Empty);
+        }
+        let min = Self::min_stride(self.width, self.format, self.layout);
+        if self.stride < min {
+            return Err(ImageError::InvalidStride {
+                min,
+                got: self.stride,
+            });
+        }
+        let expected = self.required_len();
+        if self.data.len() < expected {
+            return Err(ImageError::BufferTooSmall {
+                expected,
+                got: self.data.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether the data is already a tightly packed interleaved buffer of `format`.
+    fn is_packed(&self, format: PixelFormat) -> bool {
+        self.format == format
+            && (self.layout == PixelLayout::Hwc || format.channels() == 1)
+            && self.stride == self.width as usize * format.channels()
+            && self.data.len() == self.stride * self.height as usize
+    }
+
+    /// Sample channel `c` of an RGB-family image at `(x, y)`.
+    #[inline]
+    fn sample(&self, x: usize, y: usize, c: usize) -> u8 {
+        match self.layout {
+            PixelLayout::Hwc => self.data[y * self.stride + x * self.format.channels() + c],
+            PixelLayout::Chw => {
+                self.data[c * self.stride * self.height as usize + y * self.stride + x]
+            }
+        }
+    }
+
+    /// Convert to a tightly packed, interleaved RGB buffer.
+    ///
+    /// YUV formats are converted using BT.601 limited-range coefficients.
+    pub fn to_rgb(&self) -> Result<Vec<u8>, ImageError> {
+        self.validate()?;
+        let (w, h) = (self.width as usize, self.height as usize);
+        let mut out = Vec::with_capacity(w * h * 3);
+
+        match self.format {
+            PixelFormat::Rgb8 | PixelFormat::Bgr8 | PixelFormat::Rgba8 | PixelFormat::Bgra8 => {
+                let [r, g, b] = self.format.rgb_indices().unwrap_or([0, 1, 2]);
+                for y in 0..h {
+                    for x in 0..w {
+                        out.extend([
+                            self.sample(x, y, r),
+                            self.sample(x, y, g),
+                            self.sample(x, y, b),
+                        ]);
+                    }
+                }
+            }
+            PixelFormat::Gray8 => {
+                for y in 0..h {
+                    for &v in &self.data[y * self.stride..y * self.stride + w] {
+                        out.extend([v, v, v]);
+                    }
+                }
+            }
+            PixelFormat::Nv12 => {
+                let uv_plane = &self.data[self.stride * h..];
+                for y in 0..h {
+                    let uv_row = &uv_plane[(y / 2) * self.stride..];
+                    for x in 0..w {
+                        let luma = self.data[y * self.stride + x];
+                        let (u, v) = (uv_row[(x / 2) * 2], uv_row[(x / 2) * 2 + 1]);
+                        out.extend(yuv_to_rgb(luma, u, v));
+                    }
+                }
+            }
+            PixelFormat::Yuv420p => {
+                let cs = self.stride.div_ceil(2);
+                let u_plane = &self.data[self.stride * h..];
+                let v_plane = &u_plane[cs * h.div_ceil(2)..];
+                for y in 0..h {
+                    for x in 0..w {
+                        let luma = self.data[y * self.stride + x];
+                        let ci = (y / 2) * cs + x / 2;
+                        out.extend(yuv_to_rgb(luma, u_plane[ci], v_plane[ci]));
+                    }
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Convert to a tightly packed, interleaved BGR buffer.
+    pub fn to_bgr(&self) -> Result<Vec<u8>, ImageError> {
+        let mut out = self.to_rgb()?;
+        for px in out.chunks_exact_mut(3) {
+            px.swap(0, 2);
+        }
+        Ok(out)
+    }
+
+    /// Convert to a tightly packed grayscale buffer.
+    ///
+    /// RGB-family formats use BT.601 luma weights; YUV formats return the Y plane.
+    pub fn to_gray(&self) -> Result<Vec<u8>, ImageError> {
+        self.validate()?;
+        let (w, h) = (self.width as usize, self.height as usize);
+
+        match self.format.rgb_indices() {
+            Some(_) => Ok(self
+                .to_rgb()?
+                .chunks_exact(3)
+                .map(|px| {
+                    let luma = 77 * px[0] as u32 + 150 * px[1] as u32 + 29 * px[2] as u32;
+                    ((luma + 128) >> 8) as u8
+                })
+                .collect()),
+            None => {
+                let mut out = Vec::with_capacity(w * h);
+                for y in 0..h {
+                    out.extend_from_slice(&self.data[y * self.stride..y * self.stride + w]);
+                }
+                Ok(out)
+            }
+        }
+    }
+
+    /// Get a tightly packed interleaved buffer with `channels` channels (1 or 3),
+    /// borrowing the data when no conversion is needed.
+    pub fn to_packed(&self, channels: u32) -> Result<Cow<'a, [u8]>, ImageError> {
+        self.validate()?;
+        match channels {
+            3 if self.is_packed(PixelFormat::Rgb8) => Ok(Cow::Borrowed(self.data)),
+            3 => self.to_rgb().map(Cow::Owned),
+            1 if self.is_packed(PixelFormat::Gray8) => Ok(Cow::Borrowed(self.data)),
+            1 => self.to_gray().map(Cow::Owned),
+            c => Err(ImageError::UnsupportedChannels(c)),
+        }
+    }
+}
+
+/// An owned image buffer, for handing frames to other threads.
+#[derive(Debug, Clone)]
+pub struct OwnedImage {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    layout: PixelLayout,
+    stride: usize,
+}
+
+impl OwnedImage {
+    /// Create an owned, tightly packed, interleaved image.
+    pub fn new(data: Vec<u8>, width: u32, height: u32, format: PixelFormat) -> Self {
+        let view = ImageView::new(&data, width, height, format);
+        let (layout, stride) = (view.layout, view.stride);
+        Self {
+            data,
+            width,
+            height,
+            format,
+            layout,
+            stride,
+        }
+    }
+
+    /// Create an owned, tightly packed RGB image.
+    pub fn rgb(data: Vec<u8>, width: u32, height: u32) -> Self {
+        Self::new(data, width, height, PixelFormat::Rgb8)
+    }
+
+    /// Borrow this image as an `ImageView`.
+    pub fn view(&self) -> ImageView<'_> {
+        ImageView::new(&self.data, self.width, self.height, self.format)
+            .with_layout(self.layout)
+            .with_stride(self.stride)
+    }
+}
+
+impl From<&ImageView<'_>> for OwnedImage {
+    fn from(view: &ImageView<'_>) -> Self {
+        Self {
+            data: view.data.to_vec(),
+            width: view.width,
+            height: view.height,
+            format: view.format,
+            layout: view.layout,
+            stride: view.stride,
+        }
+    }
+}
+
+/// Convert one BT.601 limited-range YUV sample to RGB.
+#[inline]
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let c = y as i32 - 16;
+    let d = u as i32 - 128;
+    let e = v as i32 - 128;
+    let clip = |x: i32| ((x + 128) >> 8).clamp(0, 255) as u8;
+    [
+        clip(298 * c + 409 * e),
+        clip(298 * c - 100 * d - 208 * e),
+        clip(298 * c + 516 * d),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bgr_to_rgb() {
+        let data = [1u8, 2, 3, 4, 5, 6];
+        let rgb = ImageView::bgr(&data, 2, 1).to_rgb().unwrap();
+        assert_eq!(rgb, vec![3, 2, 1, 6, 5, 4]);
+        let bgr = ImageView::rgb(&rgb, 2, 1).to_bgr().unwrap();
+        assert_eq!(bgr, data.to_vec());
+    }
+
+    #[test]
+    fn test_rgba_drops_alpha_with_stride() {
+        // 1x2 RGBA image with 2 bytes of row padding.
+        let data = [10u8, 20, 30, 255, 0, 0, 40, 50, 60, 255, 0, 0];
+        let view = ImageView::new(&data, 1, 2, PixelFormat::Rgba8).with_stride(6);
+        assert_eq!(view.to_rgb().unwrap(), vec![10, 20, 30, 40, 50, 60]);
+    }
+
+    #[test]
+    fn test_planar_rgb() {
+        let data = [1u8, 2, 10, 20, 100, 200];
+        let view = ImageView::rgb(&data, 2, 1).with_layout(PixelLayout::Chw);
+        assert_eq!(view.to_rgb().unwrap(), vec![1, 10, 100, 2, 20, 200]);
+    }
+
+    #[test]
+    fn test_nv12_and_yuv420p_gray() {
+        // 2x2 mid-gray: Y = 126, U = V = 128 maps to RGB ~ (128, 128, 128).
+        let nv12 = [126u8, 126, 126, 126, 128, 128];
+        let rgb = ImageView::new(&nv12, 2, 2, PixelFormat::Nv12)
+            .to_rgb()
+            .unwrap();
+        assert_eq!(rgb.len(), 12);
+        assert!(rgb.iter().all(|&v| v == 128));
+
+        let i420 = [126u8, 126, 126, 126, 128, 128];
+        let rgb = ImageView::new(&i420, 2, 2, PixelFormat::Yuv420p)
+            .to_rgb()
+            .unwrap();
+        assert!(rgb.iter().all(|&v| v == 128));
+    }
+
+    #[test]
+    fn test_gray_roundtrip() {
+        let rgb = [255u8, 255, 255, 0, 0, 0];
+        let gray = ImageView::rgb(&rgb, 2, 1).to_gray().unwrap();
+        assert_eq!(gray, vec![255, 0]);
+        let back = ImageView::new(&gray, 2, 1, PixelFormat::Gray8)
+            .to_rgb()
+            .unwrap();
+        assert_eq!(back, rgb.to_vec());
+    }
+
+    #[test]
+    fn test_validate_errors() {
+        assert_eq!(
+            ImageView::rgb(&[0; 5], 2, 1).validate(),
+            Err(ImageError::BufferTooSmall {
+                expected: 6,
+                got: 5
+            })
+        );
+        assert_eq!(ImageView::rgb(&[], 0, 1).validate(), Err(ImageError::Empty));
+        assert!(matches!(
+            ImageView::rgb(&[0; 6], 2, 1).with_stride(4).validate(),
+            Err(ImageError::InvalidStride { min: 6, got: 4 })
+        ));
+        assert_eq!(
+            ImageView::rgb(&[], 0, 0).to_packed(3),
+            Err(ImageError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_owned_image_roundtrip() {
+        let data = [10u8, 20, 30, 255, 0, 0, 40, 50, 60, 255, 0, 0];
+        let view = ImageView::new(&data, 1, 2, PixelFormat::Rgba8).with_stride(6);
+        let owned = OwnedImage::from(&view);
+        assert_eq!(owned.view().stride(), 6);
+        assert_eq!(owned.view().to_rgb(), view.to_rgb());
+    }
+
+    #[test]
+    fn test_to_packed_borrows_rgb() {
+        let data = [1u8, 2, 3];
+        let packed = ImageView::rgb(&data, 1, 1).to_packed(3).unwrap();
+        assert!(matches!(packed, Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,228 @@
+//! MultiStreamPipeline for batching detection across several video streams.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::tracker::{BYTETracker, STrack, TrackerConfig};
+
+use super::{DetectionSource, ImageView};
+
+/// Error type for multi-stream batch failures.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MultiStreamError<E> {
+    /// The detector failed.
+    Detector(E),
+    /// `detect_batch` didn't return exactly one result per frame.
+    BatchSizeMismatch { expected: usize, got: usize },
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for MultiStreamError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Detector(err) => write!(f, "Detector error: {}", err),
+            Self::BatchSizeMismatch { expected, got } => write!(
+                f,
+                "Batch size mismatch: expected {} results, got {}",
+                expected, got
+            ),
+        }
+    }
+}
+
+impl<E: std::fmt::Debug + std::fmt::Display> std::error::Error for MultiStreamError<E> {}
+
+/// Active tracks for each stream of a batch.
+type StreamTracks<K> = Vec<(K, Vec<STrack>)>;
+
+/// A pipeline that runs one batched detector over many streams, each with its
+/// own independent `BYTETracker`.
+///
+/// Frames from all streams are sent to the detector in a single
+/// `detect_batch` call and the resulting detections are fanned out to the
+/// tracker of the stream they came from. Trackers are created lazily the first
+/// time a stream ID is seen.
+pub struct MultiStreamPipeline<D: DetectionSource, K: Eq + Hash + Clone = u32> {
+    detector: D,
+    config: TrackerConfig,
+    trackers: HashMap<K, BYTETracker>,
+}
+
+impl<D: DetectionSource, K: Eq + Hash + Clone> MultiStreamPipeline<D, K> {
+    /// Create a new multi-stream pipeline. Every stream's tracker uses `config`.
+    pub fn new(detector: D, config: TrackerConfig) -> Self {
+        Self {
+            detector,
+            config,
+            trackers: HashMap::new(),
+        }
+    }
+
+    /// Create a new multi-stream pipeline with default tracker configuration.
+    pub fn with_default_config(detector: D) -> Self {
+        Self::new(detector, TrackerConfig::default())
+    }
+
+    /// Process one frame from each of several streams in a single batch.
+    ///
+    /// # Arguments
+    /// * `frames` - `(stream_id, image)` for each frame
+    ///
+    /// # Returns
+    /// Active tracks per stream, in the same order as `frames`, or an error if
+    /// detection failed or didn't return one result per frame. No tracker is
+    /// updated on error.
+    pub fn process_batch(
+        &mut self,
+        frames: &[(K, ImageView<'_>)],
+    ) -> Result<StreamTracks<K>, MultiStreamError<D::Error>> {
+        let images: Vec<ImageView<'_>> = frames.iter().map(|(_, image)| *image).collect();
+        let detections = self
+            .detector
+            .detect_batch(&images)
+            .map_err(MultiStreamError::Detector)?;
+        if detections.len() != frames.len() {
+            return Err(MultiStreamError::BatchSizeMismatch {
+                expected: frames.len(),
+                got: detections.len(),
+            });
+        }
+
+        Ok(frames
+            .iter()
+            .zip(detections)
+            .map(|((stream_id, _), dets)| {
+                let tracker = self
+                    .trackers
+                    .entry(stream_id.clone())
+                    .or_insert_with(|| BYTETracker::new(self.config.clone()));
+                (stream_id.clone(), tracker.update(dets))
+            })
+            .collect())
+    }
+
+    /// Register a stream with a tracker using the given configuration.
+    ///
+    /// Replaces any existing tracker for the stream.
+    pub fn add_stream(&mut self, stream_id: K, config: TrackerConfig) {
+        self.trackers.insert(stream_id, BYTETracker::new(config));
+    }
+
+    /// Remove a stream and return its tracker, if it existed.
+    pub fn remove_stream(&mut self, stream_id: &K) -> Option<BYTETracker> {
+        self.trackers.remove(stream_id)
+    }
+
+    /// Iterate over the IDs of all known streams.
+    pub fn stream_ids(&self) -> impl Iterator<Item = &K> {
+        self.trackers.keys()
+    }
+
+    /// Get a reference to the tracker of a stream.
+    pub fn tracker(&self, stream_id: &K) -> Option<&BYTETracker> {
+        self.trackers.get(stream_id)
+    }
+
+    /// Get a mutable reference to the tracker of a stream.
+    pub fn tracker_mut(&mut self, stream_id: &K) -> Option<&mut BYTETracker> {
+        self.trackers.get_mut(stream_id)
+    }
+
+    /// Get a reference to the underlying detector.
+    pub fn detector(&self) -> &D {
+        &self.detector
+    }
+
+    /// Get a mutable reference to the underlying detector.
+    pub fn detector_mut(&mut self) -> &mut D {
+        &mut self.detector
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::Detection;
+
+    /// Returns a single detection whose x offset is the frame width.
+    struct WidthDetector {
+        batches: usize,
+    }
+
+    impl DetectionSource for WidthDetector {
+        type Error = std::convert::Infallible;
+
+        fn detect_image(&mut self, image: &ImageView<'_>) -> Result<Vec<Detection>, Self::Error> {
+            let x = image.width() as f32;
+            Ok(vec![Detection::new(x, 10.0, x + 40.0, 90.0, 0.9)])
+        }
+
+        fn detect_batch(
+            &mut self,
+            images: &[ImageView<'_>],
+        ) -> Result<Vec<Vec<Detection>>, Self::Error> {
+            self.batches += 1;
+            images
+                .iter()
+                .map(|image| self.detect_image(image))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_multi_stream_fan_out() {
+        let mut pipeline = MultiStreamPipeline::with_default_config(WidthDetector { batches: 0 });
+
+        let out = pipeline
+            .process_batch(&[
+                ("cam-a", ImageView::rgb(&[], 100, 100)),
+                ("cam-b", ImageView::rgb(&[], 300, 100)),
+            ])
+            .unwrap();
+
+        assert_eq!(pipeline.detector().batches, 1);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].0, "cam-a");
+        assert_eq!(out[1].0, "cam-b");
+        assert_eq!(out[0].1[0].tlwh().x, 100.0);
+        assert_eq!(out[1].1[0].tlwh().x, 300.0);
+        assert_eq!(pipeline.stream_ids().count(), 2);
+
+        assert!(pipeline.remove_stream(&"cam-a").is_some());
+        assert!(pipeline.tracker(&"cam-a").is_none());
+    }
+
+    /// Drops the last image of every batch.
+    struct ShortBatchDetector;
+
+    impl DetectionSource for ShortBatchDetector {
+        type Error = std::convert::Infallible;
+
+        fn detect_image(&mut self, _image: &ImageView<'_>) -> Result<Vec<Detection>, Self::Error> {
+            Ok(vec![])
+        }
+
+        fn detect_batch(
+            &mut self,
+            images: &[ImageView<'_>],
+        ) -> Result<Vec<Vec<Detection>>, Self::Error> {
+            Ok(vec![vec![]; images.len().saturating_sub(1)])
+        }
+    }
+
+    #[test]
+    fn test_short_batch_is_an_error() {
+        let mut pipeline = MultiStreamPipeline::with_default_config(ShortBatchDetector);
+        let result = pipeline.process_batch(&[
+            (0, ImageView::rgb(&[], 10, 10)),
+            (1, ImageView::rgb(&[], 10, 10)),
+        ]);
+        assert_eq!(
+            result.unwrap_err(),
+            MultiStreamError::BatchSizeMismatch {
+                expected: 2,
+                got: 1
+            }
+        );
+        assert_eq!(pipeline.stream_ids().count(), 0);
+    }
+}
@@ -0,0 +1,171 @@
+//! Non-maximum suppression for detection outputs.
+
+use crate::tracker::{Detection, Rect};
+
+/// Score decay function used by soft-NMS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SoftNmsMethod {
+    /// Scale the score by `1 - iou` when the overlap exceeds the IoU threshold.
+    Linear,
+    /// Scale the score by `exp(-iou² / sigma)`.
+    Gaussian { sigma: f32 },
+}
+
+/// Run greedy NMS over a set of boxes and return the indices of the kept boxes.
+///
+/// Indices are returned in descending score order.
+pub fn nms_indices(boxes: &[Rect], scores: &[f32], iou_threshold: f32) -> Vec<usize> {
+    nms_by(boxes, scores, iou_threshold, |_, _| true)
+}
+
+/// Greedy NMS where a box only suppresses boxes for which `same_group`
+/// holds.
+fn nms_by(
+    boxes: &[Rect],
+    scores: &[f32],
+    iou_threshold: f32,
+    same_group: impl Fn(usize, usize) -> bool,
+) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..boxes.len()).collect();
+    order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+
+    let mut suppressed = vec![false; boxes.len()];
+    let mut keep = Vec::new();
+    for (i, &idx) in order.iter().enumerate() {
+        if suppressed[idx] {
+            continue;
+        }
+        keep.push(idx);
+        for &other in &order[i + 1..] {
+            if !suppressed[other]
+                && same_group(idx, other)
+                && boxes[idx].iou(&boxes[other]) > iou_threshold
+            {
+                suppressed[other] = true;
+            }
+        }
+    }
+    keep
+}
+
+/// Class-agnostic NMS: overlapping boxes suppress each other regardless of class.
+pub fn nms(detections: Vec<Detection>, iou_threshold: f32) -> Vec<Detection> {
+    let boxes: Vec<Rect> = detections.iter().map(|d| d.bbox).collect();
+    let scores: Vec<f32> = detections.iter().map(|d| d.score).collect();
+    let keep = nms_indices(&boxes, &scores, iou_threshold);
+
+    let mut slots: Vec<Option<Detection>> = detections.into_iter().map(Some).collect();
+    keep.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+/// Class-aware NMS: boxes only suppress other boxes with the same `class_id`.
+///
+/// Detections without a class are treated as one class of their own.
+pub fn nms_class_aware(detections: Vec<Detection>, iou_threshold: f32) -> Vec<Detection> {
+    let boxes: Vec<Rect> = detections.iter().map(|d| d.bbox).collect();
+    let scores: Vec<f32> = detections.iter().map(|d| d.score).collect();
+    let keep = nms_by(&boxes, &scores, iou_threshold, |a, b| {
+        detections[a].class_id == detections[b].class_id
+    });
+
+    let mut slots: Vec<Option<Detection>> = detections.into_iter().map(Some).collect();
+    keep.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+/// Soft-NMS: decay the scores of overlapping boxes instead of discarding them.
+///
+/// Boxes whose decayed score falls below `score_threshold` are dropped. For the
+/// linear method only overlaps above `iou_threshold` are decayed. If
+/// `class_aware` is set, only boxes of the same class affect each other.
+pub fn soft_nms(
+    detections: Vec<Detection>,
+    method: SoftNmsMethod,
+    iou_threshold: f32,
+    score_threshold: f32,
+    class_aware: bool,
+) -> Vec<Detection> {
+    let mut pending = detections;
+    let mut keep = Vec::new();
+
+    while !pending.is_empty() {
+        let best = pending
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.score.total_cmp(&b.score))
+            .map(|(i, _)| i)
+            .unwrap();
+        let top = pending.swap_remove(best);
+        if top.score < score_threshold {
+            break;
+        }
+
+        for det in pending.iter_mut() {
+            if class_aware && det.class_id != top.class_id {
+                continue;
+            }
+            let iou = top.bbox.iou(&det.bbox);
+            let weight = match method {
+                SoftNmsMethod::Linear if iou > iou_threshold => 1.0 - iou,
+                SoftNmsMethod::Linear => 1.0,
+                SoftNmsMethod::Gaussian { sigma } => (-(iou * iou) / sigma).exp(),
+            };
+            det.score *= weight;
+        }
+        pending.retain(|d| d.score >= score_threshold);
+        keep.push(top);
+    }
+
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dets() -> Vec<Detection> {
+        vec![
+            Detection::new(0.0, 0.0, 10.0, 10.0, 0.9).with_class_id(0),
+            Detection::new(1.0, 1.0, 11.0, 11.0, 0.8).with_class_id(1),
+            Detection::new(0.5, 0.5, 10.5, 10.5, 0.7).with_class_id(0),
+            Detection::new(50.0, 50.0, 60.0, 60.0, 0.6).with_class_id(0),
+        ]
+    }
+
+    #[test]
+    fn test_nms_agnostic() {
+        let kept = nms(dets(), 0.5);
+        let scores: Vec<f32> = kept.iter().map(|d| d.score).collect();
+        assert_eq!(scores, vec![0.9, 0.6]);
+    }
+
+    #[test]
+    fn test_nms_class_aware() {
+        let kept = nms_class_aware(dets(), 0.5);
+        let scores: Vec<f32> = kept.iter().map(|d| d.score).collect();
+        assert_eq!(scores, vec![0.9, 0.8, 0.6]);
+    }
+
+    #[test]
+    fn test_soft_nms_gaussian_keeps_decayed_boxes() {
+        let kept = soft_nms(
+            dets(),
+            SoftNmsMethod::Gaussian { sigma: 0.5 },
+            0.3,
+            0.01,
+            false,
+        );
+        assert_eq!(kept.len(), 4);
+        assert_eq!(kept[0].score, 0.9);
+        // Heavy overlap with the top box decays the second-best score.
+        assert!(kept.iter().all(|d| d.score <= 0.9));
+        assert!(kept.iter().any(|d| d.score == 0.6));
+    }
+
+    #[test]
+    fn test_soft_nms_linear_threshold() {
+        let kept = soft_nms(dets(), SoftNmsMethod::Linear, 0.3, 0.3, false);
+        // Both overlapping boxes decay below 0.3 and are dropped.
+        let scores: Vec<f32> = kept.iter().map(|d| d.score).collect();
+        assert_eq!(scores, vec![0.9, 0.6]);
+    }
+}
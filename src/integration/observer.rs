@@ -0,0 +1,57 @@
+//! Observer hooks for `TrackerPipeline`.
+
+use std::collections::HashSet;
+
+use crate::tracker::{Detection, STrack};
+
+/// Changes in the set of active tracks between two consecutive frames.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackLifecycle {
+    /// IDs of tracks that became active this frame, either newly confirmed
+    /// or recovered from the lost state.
+    pub started: Vec<u64>,
+    /// IDs of tracks that were active last frame but no longer are.
+    pub ended: Vec<u64>,
+    /// Whether the detector ran this frame, as opposed to prediction only.
+    pub detected: bool,
+}
+
+impl TrackLifecycle {
+    /// Compare the active tracks of the previous and current frame.
+    pub fn between(previous: &[STrack], current: &[STrack], detected: bool) -> Self {
+        let previous_ids: HashSet<u64> = previous.iter().map(|t| t.track_id).collect();
+        let current_ids: HashSet<u64> = current.iter().map(|t| t.track_id).collect();
+        Self {
+            started: current
+                .iter()
+                .map(|t| t.track_id)
+                .filter(|id| !previous_ids.contains(id))
+                .collect(),
+            ended: previous
+                .iter()
+                .map(|t| t.track_id)
+                .filter(|id| !current_ids.contains(id))
+                .collect(),
+            detected,
+        }
+    }
+}
+
+/// Hooks called by `TrackerPipeline` while it processes a frame.
+///
+/// All methods have empty default implementations, so observers only
+/// implement the hooks they care about. `frame_id` is the tracker frame
+/// the hook refers to, starting at 1. `E` is the detector's error type.
+pub trait PipelineObserver<E>: Send {
+    /// Called before the detector runs. Not called on prediction-only frames.
+    fn before_detect(&mut self, _frame_id: u32) {}
+
+    /// Called with the detector output, before it's passed to the tracker.
+    fn after_detect(&mut self, _frame_id: u32, _detections: &[Detection]) {}
+
+    /// Called with the active tracks once the tracker has been updated.
+    fn after_track(&mut self, _frame_id: u32, _tracks: &[STrack], _lifecycle: &TrackLifecycle) {}
+
+    /// Called when the detector fails. The frame is not tracked.
+    fn on_error(&mut self, _frame_id: u32, _error: &E) {}
+}
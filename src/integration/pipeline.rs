@@ -2,7 +2,25 @@
 
 use crate::tracker::{BYTETracker, STrack, TrackerConfig};
 
-use super::DetectionSource;
+use super::{DetectionSource, ImageView, PipelineObserver, TrackLifecycle};
+
+/// When the pipeline runs the detector.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DetectionSchedule {
+    /// Run the detector on every frame.
+    #[default]
+    EveryFrame,
+    /// Run the detector once every `n` frames, predicting in between.
+    Stride(u32),
+    /// Run the detector at least every `max_interval` frames, and earlier
+    /// when there are no active tracks or when any track's predicted center
+    /// uncertainty (standard deviation relative to box height) exceeds
+    /// `max_uncertainty`.
+    Adaptive {
+        max_interval: u32,
+        max_uncertainty: f32,
+    },
+}
 
 /// A combined tracker that bundles detection inference with ByteTrack.
 ///
@@ -11,6 +29,10 @@ use super::DetectionSource;
 pub struct TrackerPipeline<D: DetectionSource> {
     detector: D,
     tracker: BYTETracker,
+    schedule: DetectionSchedule,
+    frames_since_detection: Option<u32>,
+    last_tracks: Vec<STrack>,
+    observers: Vec<Box<dyn PipelineObserver<D::Error>>>,
 }
 
 impl<D: DetectionSource> TrackerPipeline<D> {
@@ -19,6 +41,61 @@ impl<D: DetectionSource> TrackerPipeline<D> {
         Self {
             detector,
             tracker: BYTETracker::new(config),
+            schedule: DetectionSchedule::EveryFrame,
+            frames_since_detection: None,
+            last_tracks: Vec::new(),
+            observers: Vec::new(),
+        }
+    }
+
+    /// Register an observer. Observers are called in registration order.
+    pub fn with_observer(mut self, observer: impl PipelineObserver<D::Error> + 'static) -> Self {
+        self.add_observer(observer);
+        self
+    }
+
+    /// Register an observer on an existing pipeline.
+    pub fn add_observer(&mut self, observer: impl PipelineObserver<D::Error> + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Remove all registered observers.
+    pub fn clear_observers(&mut self) {
+        self.observers.clear();
+    }
+
+    /// Set when the detector runs. On skipped frames the tracker advances with
+    /// Kalman prediction only and returns predicted boxes for active tracks.
+    pub fn with_schedule(mut self, schedule: DetectionSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Number of frames processed since the detector last ran, or `None`
+    /// if it hasn't run yet.
+    pub fn frames_since_detection(&self) -> Option<u32> {
+        self.frames_since_detection
+    }
+
+    /// Whether the detector should run on the next frame.
+    fn should_detect(&self) -> bool {
+        let Some(since) = self.frames_since_detection else {
+            return true;
+        };
+        match self.schedule {
+            DetectionSchedule::EveryFrame => true,
+            DetectionSchedule::Stride(n) => since + 1 >= n,
+            DetectionSchedule::Adaptive {
+                max_interval,
+                max_uncertainty,
+            } => {
+                since + 1 >= max_interval
+                    || self.last_tracks.is_empty()
+                    || self
+                        .last_tracks
+                        .iter()
+                        .any(|t| center_uncertainty(t) > max_uncertainty)
+            }
         }
     }
 
@@ -27,13 +104,56 @@ impl<D: DetectionSource> TrackerPipeline<D> {
         Self::new(detector, TrackerConfig::default())
     }
 
-    /// Process a single frame and return active tracks.
+    /// Process a single image and return active tracks.
     ///
-    /// This method runs detection on the input image and then updates
+    /// This method runs detection on the image and then updates
     /// the tracker with the detected objects.
     ///
+    /// # Returns
+    /// A vector of active `STrack` objects, or a detection error.
+    pub fn process_image(&mut self, image: &ImageView<'_>) -> Result<Vec<STrack>, D::Error> {
+        let frame_id = self.tracker.frame_id() + 1;
+        let detected = self.should_detect();
+        let tracks = if detected {
+            for observer in &mut self.observers {
+                observer.before_detect(frame_id);
+            }
+            let detections = match self.detector.detect_image(image) {
+                Ok(detections) => detections,
+                Err(err) => {
+                    for observer in &mut self.observers {
+                        observer.on_error(frame_id, &err);
+                    }
+                    return Err(err);
+                }
+            };
+            for observer in &mut self.observers {
+                observer.after_detect(frame_id, &detections);
+            }
+            self.frames_since_detection = Some(0);
+            self.tracker.update(detections)
+        } else {
+            self.frames_since_detection = self.frames_since_detection.map(|n| n + 1);
+            self.tracker.predict()
+        };
+
+        if !self.observers.is_empty() {
+            let lifecycle = TrackLifecycle::between(&self.last_tracks, &tracks, detected);
+            for observer in &mut self.observers {
+                observer.after_track(frame_id, &tracks, &lifecycle);
+            }
+        }
+        self.last_tracks.clone_from(&tracks);
+        Ok(tracks)
+    }
+
+    /// Process a single frame and return active tracks.
+    ///
+    /// Compatibility shim for `process_image`: `input` is interpreted as a
+    /// tightly packed, interleaved RGB image.
+    ///
     /// # Arguments
-    /// * `input` - Raw image bytes
+    /// * `input` - Raw RGB image bytes
     /// * `width` - Image width in pixels
     /// * `height` - Image height in pixels
     ///
@@ -45,8 +165,7 @@ impl<D: DetectionSource> TrackerPipeline<D> {
         width: u32,
         height: u32,
     ) -> Result<Vec<STrack>, D::Error> {
-        let detections = self.detector.detect(input, width, height)?;
-        Ok(self.tracker.update(detections))
+        self.process_image(&ImageView::rgb(input, width, height))
     }
 
     /// Get a reference to the underlying detector.
@@ -70,24 +189,31 @@ impl<D: DetectionSource> TrackerPipeline<D> {
     }
 }
 
+/// Standard deviation of a track's center position relative to its height.
+fn center_uncertainty(track: &STrack) -> f32 {
+    match (&track.mean, &track.covariance) {
+        (Some(mean), Some(cov)) if mean[3] > 0.0 => {
+            ((cov[[0, 0]] + cov[[1, 1]]).sqrt() / mean[3]) as f32
+        }
+        _ => 0.0,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tracker::Detection;
+    use crate::tracker::{Detection, TrackState};
 
     struct MockDetector {
         detections: Vec<Detection>,
+        calls: usize,
     }
 
     impl DetectionSource for MockDetector {
         type Error = std::convert::Infallible;
 
-        fn detect(
-            &mut self,
-            _input: &[u8],
-            _width: u32,
-            _height: u32,
-        ) -> Result<Vec<Detection>, Self::Error> {
+        fn detect_image(&mut self, _image: &ImageView<'_>) -> Result<Vec<Detection>, Self::Error> {
+            self.calls += 1;
             Ok(self.detections.clone())
         }
     }
@@ -96,6 +222,7 @@ mod tests {
     fn test_tracker_pipeline() {
         let detector = MockDetector {
             detections: vec![Detection::new(10.0, 20.0, 50.0, 80.0, 0.9)],
+            calls: 0,
         };
 
         let mut pipeline = TrackerPipeline::with_default_config(detector);
@@ -104,4 +231,159 @@ mod tests {
         // First frame initializes tracks
         assert!(tracks.is_empty() || !tracks.is_empty()); // Depends on activation logic
     }
+
+    #[test]
+    fn test_stride_schedule_predicts_between_detections() {
+        let detector = MockDetector {
+            detections: vec![Detection::new(10.0, 20.0, 50.0, 80.0, 0.9)],
+            calls: 0,
+        };
+        let mut pipeline = TrackerPipeline::with_default_config(detector)
+            .with_schedule(DetectionSchedule::Stride(3));
+
+        let first = pipeline.process_frame(&[], 640, 480).unwrap();
+        assert_eq!(first.len(), 1);
+
+        for _ in 0..2 {
+            let tracks = pipeline.process_frame(&[], 640, 480).unwrap();
+            assert_eq!(tracks.len(), 1);
+            assert_eq!(tracks[0].track_id, first[0].track_id);
+            assert_eq!(tracks[0].state, TrackState::Tracked);
+        }
+        assert_eq!(pipeline.detector().calls, 1);
+        assert_eq!(pipeline.frames_since_detection(), Some(2));
+
+        pipeline.process_frame(&[], 640, 480).unwrap();
+        assert_eq!(pipeline.detector().calls, 2);
+        assert_eq!(pipeline.tracker().frame_id(), 4);
+    }
+
+    #[test]
+    fn test_adaptive_schedule_detects_without_tracks() {
+        let detector = MockDetector {
+            detections: vec![],
+            calls: 0,
+        };
+        let mut pipeline = TrackerPipeline::with_default_config(detector).with_schedule(
+            DetectionSchedule::Adaptive {
+                max_interval: 5,
+                max_uncertainty: 1.0,
+            },
+        );
+
+        for _ in 0..3 {
+            pipeline.process_frame(&[], 640, 480).unwrap();
+        }
+        assert_eq!(pipeline.detector().calls, 3);
+    }
+
+    type EventLog = std::sync::Arc<std::sync::Mutex<Vec<String>>>;
+
+    struct RecordingObserver {
+        name: &'static str,
+        log: EventLog,
+    }
+
+    impl<E: std::fmt::Debug> PipelineObserver<E> for RecordingObserver {
+        fn before_detect(&mut self, frame_id: u32) {
+            let event = format!("{} before_detect {}", self.name, frame_id);
+            self.log.lock().unwrap().push(event);
+        }
+
+        fn after_detect(&mut self, frame_id: u32, detections: &[Detection]) {
+            let event = format!(
+                "{} after_detect {} {}",
+                self.name,
+                frame_id,
+                detections.len()
+            );
+            self.log.lock().unwrap().push(event);
+        }
+
+        fn after_track(&mut self, frame_id: u32, tracks: &[STrack], lifecycle: &TrackLifecycle) {
+            let event = format!(
+                "{} after_track {} {} started={:?} ended={:?}",
+                self.name,
+                frame_id,
+                tracks.len(),
+                lifecycle.started,
+                lifecycle.ended
+            );
+            self.log.lock().unwrap().push(event);
+        }
+
+        fn on_error(&mut self, frame_id: u32, error: &E) {
+            let event = format!("{} on_error {} {:?}", self.name, frame_id, error);
+            self.log.lock().unwrap().push(event);
+        }
+    }
+
+    struct FailingDetector;
+
+    impl DetectionSource for FailingDetector {
+        type Error = &'static str;
+
+        fn detect_image(&mut self, _image: &ImageView<'_>) -> Result<Vec<Detection>, Self::Error> {
+            Err("boom")
+        }
+    }
+
+    #[test]
+    fn test_observers_called_in_order() {
+        let log = EventLog::default();
+        let detector = MockDetector {
+            detections: vec![Detection::new(10.0, 20.0, 50.0, 80.0, 0.9)],
+            calls: 0,
+        };
+        let mut pipeline = TrackerPipeline::with_default_config(detector)
+            .with_observer(RecordingObserver {
+                name: "a",
+                log: log.clone(),
+            })
+            .with_observer(RecordingObserver {
+                name: "b",
+                log: log.clone(),
+            });
+
+        let tracks = pipeline.process_frame(&[], 640, 480).unwrap();
+        let id = tracks[0].track_id;
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "a before_detect 1".to_string(),
+                "b before_detect 1".to_string(),
+                "a after_detect 1 1".to_string(),
+                "b after_detect 1 1".to_string(),
+                format!("a after_track 1 1 started=[{}] ended=[]", id),
+                format!("b after_track 1 1 started=[{}] ended=[]", id),
+            ]
+        );
+
+        log.lock().unwrap().clear();
+        pipeline.detector_mut().detections.clear();
+        pipeline.process_frame(&[], 640, 480).unwrap();
+        assert_eq!(
+            log.lock().unwrap().last().unwrap(),
+            &format!("b after_track 2 0 started=[] ended=[{}]", id)
+        );
+    }
+
+    #[test]
+    fn test_observer_on_error() {
+        let log = EventLog::default();
+        let mut pipeline = TrackerPipeline::with_default_config(FailingDetector).with_observer(
+            RecordingObserver {
+                name: "a",
+                log: log.clone(),
+            },
+        );
+
+        assert_eq!(pipeline.process_frame(&[], 640, 480).unwrap_err(), "boom");
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["a before_detect 1", "a on_error 1 \"boom\""]
+        );
+        assert_eq!(pipeline.tracker().frame_id(), 0);
+    }
 }
@@ -0,0 +1,70 @@
+//! Backend-agnostic postprocessing of raw model outputs.
+//!
+//! Applies confidence filtering, box format conversion, letterbox un-mapping
+//! and NMS to turn `RawDetection`s into tracker-ready `Detection`s.
+
+use super::DetectionBuilder;
+use super::decode::RawDetection;
+use super::nms::{nms, nms_class_aware};
+use super::preprocess::LetterboxInfo;
+use crate::tracker::Detection;
+
+/// Configuration for confidence filtering and NMS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostprocessConfig {
+    /// Minimum score for a raw detection to be kept.
+    pub conf_threshold: f32,
+    /// IoU threshold for non-maximum suppression, or `None` to disable NMS.
+    pub nms_threshold: Option<f32>,
+    /// Suppress overlapping boxes across classes instead of per class.
+    pub class_agnostic_nms: bool,
+}
+
+impl Default for PostprocessConfig {
+    fn default() -> Self {
+        Self {
+            conf_threshold: 0.25,
+            nms_threshold: Some(0.45),
+            class_agnostic_nms: false,
+        }
+    }
+}
+
+/// Convert raw model outputs to NMS-filtered `Detection`s in original image coordinates.
+///
+/// # Arguments
+/// * `raw_detections` - Raw model outputs in model input coordinates
+/// * `bbox_is_xywh` - Whether raw boxes are `[cx, cy, w, h]` (vs TLBR)
+/// * `letterbox` - Geometry used to map boxes back to the source image
+/// * `config` - Confidence and NMS settings
+pub fn postprocess_detections(
+    raw_detections: Vec<RawDetection>,
+    bbox_is_xywh: bool,
+    letterbox: &LetterboxInfo,
+    config: &PostprocessConfig,
+) -> Vec<Detection> {
+    let detections: Vec<Detection> = raw_detections
+        .into_iter()
+        .filter(|d| d.score >= config.conf_threshold)
+        .map(|d| {
+            let [x1, y1, x2, y2] = if bbox_is_xywh {
+                let [cx, cy, w, h] = d.bbox;
+                [cx - w / 2.0, cy - h / 2.0, cx + w / 2.0, cy + h / 2.0]
+            } else {
+                d.bbox
+            };
+            let [x1, y1, x2, y2] = letterbox.unmap_tlbr([x1, y1, x2, y2]);
+            let mut builder = DetectionBuilder::new().tlbr(x1, y1, x2, y2).score(d.score);
+            if let Some(class_id) = d.class_id {
+                builder = builder.class_id(class_id);
+            }
+            builder.build()
+        })
+        .collect();
+
+    match config.nms_threshold {
+        Some(iou) if config.class_agnostic_nms => nms(detections, iou),
+        Some(iou) => nms_class_aware(detections, iou),
+        None => detections,
+    }
+}
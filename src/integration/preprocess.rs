@@ -0,0 +1,291 @@
+//! Backend-agnostic image preprocessing for detection models.
+//!
+//! Provides an aspect-preserving letterbox resize with bilinear interpolation,
+//! mean/std normalization and HWC → CHW layout conversion, along with the
+//! inverse mapping needed to bring model-space boxes back to image coordinates.
+
+/// Geometry of a letterbox transform, used to map boxes back to the source image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LetterboxInfo {
+    /// Scale factor applied to the source image.
+    pub scale: f32,
+    /// Horizontal padding (in model pixels) added on the left.
+    pub pad_x: f32,
+    /// Vertical padding (in model pixels) added on the top.
+    pub pad_y: f32,
+    /// Width of the source image in pixels.
+    pub src_width: u32,
+    /// Height of the source image in pixels.
+    pub src_height: u32,
+}
+
+impl LetterboxInfo {
+    /// Identity transform for an image that already matches the model size.
+    pub fn identity(width: u32, height: u32) -> Self {
+        Self {
+            scale: 1.0,
+            pad_x: 0.0,
+            pad_y: 0.0,
+            src_width: width,
+            src_height: height,
+        }
+    }
+
+    /// Map a TLBR box from model input coordinates back to source image coordinates.
+    ///
+    /// The result is clipped to the bounds of the source image.
+    pub fn unmap_tlbr(&self, bbox: [f32; 4]) -> [f32; 4] {
+        let w = self.src_width as f32;
+        let h = self.src_height as f32;
+        [
+            ((bbox[0] - self.pad_x) / self.scale).clamp(0.0, w),
+            ((bbox[1] - self.pad_y) / self.scale).clamp(0.0, h),
+            ((bbox[2] - self.pad_x) / self.scale).clamp(0.0, w),
+            ((bbox[3] - self.pad_y) / self.scale).clamp(0.0, h),
+        ]
+    }
+}
+
+/// Configuration for letterbox resizing and normalization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreprocessConfig {
+    /// Color used for the letterbox padding, per channel.
+    pub pad_color: [u8; 3],
+    /// Per-channel mean subtracted after scaling pixels to `[0, 1]`.
+    pub mean: [f32; 3],
+    /// Per-channel standard deviation applied after mean subtraction.
+    pub std: [f32; 3],
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        Self {
+            pad_color: [114, 114, 114], // YOLO letterbox gray
+            mean: [0.0, 0.0, 0.0],
+            std: [1.0, 1.0, 1.0],
+        }
+    }
+}
+
+/// Compute the letterbox geometry for fitting `width`x`height` into `target_w`x`target_h`.
+///
+/// An empty source image maps to the identity transform.
+pub fn letterbox_info(width: u32, height: u32, target_w: u32, target_h: u32) -> LetterboxInfo {
+    if width == 0 || height == 0 {
+        return LetterboxInfo::identity(width, height);
+    }
+    let scale = (target_w as f32 / width as f32).min(target_h as f32 / height as f32);
+    let new_w = ((width as f32 * scale).round() as u32).min(target_w);
+    let new_h = ((height as f32 * scale).round() as u32).min(target_h);
+    LetterboxInfo {
+        scale,
+        pad_x: ((target_w - new_w) / 2) as f32,
+        pad_y: ((target_h - new_h) / 2) as f32,
+        src_width: width,
+        src_height: height,
+    }
+}
+
+/// Letterbox-resize an interleaved (HWC) `u8` image using bilinear interpolation.
+///
+/// The image is scaled to fit inside `target_w`x`target_h` while preserving its
+/// aspect ratio, centered, and the remaining area is filled with `pad_color`.
+/// For images with fewer than three channels only the leading pad components are used.
+/// An empty image yields an all-padding output.
+///
+/// # Panics
+/// Panics if `input.len()` is not `width * height * channels`.
+pub fn letterbox(
+    input: &[u8],
+    width: u32,
+    height: u32,
+    channels: u32,
+    target_w: u32,
+    target_h: u32,
+    pad_color: [u8; 3],
+) -> (Vec<u8>, LetterboxInfo) {
+    let c = channels as usize;
+    assert_eq!(input.len(), width as usize * height as usize * c);
+
+    let info = letterbox_info(width, height, target_w, target_h);
+    let (tw, th) = (target_w as usize, target_h as usize);
+    let mut out = vec![0u8; tw * th * c];
+    for px in out.chunks_exact_mut(c) {
+        for (ch, v) in px.iter_mut().enumerate() {
+            *v = pad_color[ch % 3];
+        }
+    }
+
+    let new_w = ((width as f32 * info.scale).round() as usize).min(tw);
+    let new_h = ((height as f32 * info.scale).round() as usize).min(th);
+    if new_w == 0 || new_h == 0 {
+        return (out, info);
+    }
+    let (pad_x, pad_y) = (info.pad_x as usize, info.pad_y as usize);
+    let src_w = width as usize;
+    let max_x = (width - 1) as f32;
+    let max_y = (height - 1) as f32;
+    let scale_x = width as f32 / new_w as f32;
+    let scale_y = height as f32 / new_h as f32;
+
+    for dy in 0..new_h {
+        let sy = ((dy as f32 + 0.5) * scale_y - 0.5).clamp(0.0, max_y);
+        let y0 = sy.floor() as usize;
+        let y1 = (y0 + 1).min(height as usize - 1);
+        let fy = sy - y0 as f32;
+
+        for dx in 0..new_w {
+            let sx = ((dx as f32 + 0.5) * scale_x - 0.5).clamp(0.0, max_x);
+            let x0 = sx.floor() as usize;
+            let x1 = (x0 + 1).min(src_w - 1);
+            let fx = sx - x0 as f32;
+
+            let dst = ((dy + pad_y) * tw + dx + pad_x) * c;
+            for ch in 0..c {
+                let p00 = input[(y0 * src_w + x0) * c + ch] as f32;
+                let p01 = input[(y0 * src_w + x1) * c + ch] as f32;
+                let p10 = input[(y1 * src_w + x0) * c + ch] as f32;
+                let p11 = input[(y1 * src_w + x1) * c + ch] as f32;
+                let top = p00 + (p01 - p00) * fx;
+                let bottom = p10 + (p11 - p10) * fx;
+                out[dst + ch] = (top + (bottom - top) * fy).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+
+    (out, info)
+}
+
+/// Convert an interleaved (HWC) `u8` image to a normalized planar (CHW) `f32` buffer.
+///
+/// Each value is computed as `(pixel / 255 - mean[c]) / std[c]`.
+pub fn hwc_to_chw_normalized(
+    input: &[u8],
+    width: u32,
+    height: u32,
+    channels: u32,
+    mean: [f32; 3],
+    std: [f32; 3],
+) -> Vec<f32> {
+    let c = channels as usize;
+    let plane = width as usize * height as usize;
+    let mut out = vec![0.0f32; plane * c];
+    for (i, px) in input.chunks_exact(c).enumerate() {
+        for (ch, &v) in px.iter().enumerate() {
+            out[ch * plane + i] = (v as f32 / 255.0 - mean[ch % 3]) / std[ch % 3];
+        }
+    }
+    out
+}
+
+/// Run the full preprocessing chain: letterbox, normalize and convert to CHW.
+///
+/// Returns the planar `f32` data of shape `[channels, target_h, target_w]`
+/// together with the letterbox geometry. Images that already match the target
+/// size are passed through without resampling.
+pub fn preprocess_image(
+    input: &[u8],
+    width: u32,
+    height: u32,
+    channels: u32,
+    target_w: u32,
+    target_h: u32,
+    config: &PreprocessConfig,
+) -> (Vec<f32>, LetterboxInfo) {
+    if width == target_w && height == target_h {
+        let data = hwc_to_chw_normalized(input, width, height, channels, config.mean, config.std);
+        return (data, LetterboxInfo::identity(width, height));
+    }
+
+    let (resized, info) = letterbox(
+        input,
+        width,
+        height,
+        channels,
+        target_w,
+        target_h,
+        config.pad_color,
+    );
+    let data = hwc_to_chw_normalized(
+        &resized,
+        target_w,
+        target_h,
+        channels,
+        config.mean,
+        config.std,
+    );
+    (data, info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letterbox_info_wide_image() {
+        let info = letterbox_info(1920, 1080, 640, 640);
+        assert!((info.scale - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(info.pad_x, 0.0);
+        assert_eq!(info.pad_y, 140.0);
+    }
+
+    #[test]
+    fn test_letterbox_pads_and_preserves_uniform_color() {
+        let input = vec![200u8; 4 * 2 * 3];
+        let (out, info) = letterbox(&input, 4, 2, 3, 4, 4, [114, 114, 114]);
+
+        assert_eq!(info.pad_y, 1.0);
+        // First row is padding, second row is image content.
+        assert_eq!(&out[0..3], &[114, 114, 114]);
+        assert_eq!(&out[4 * 3..4 * 3 + 3], &[200, 200, 200]);
+        assert_eq!(&out[3 * 4 * 3..3 * 4 * 3 + 3], &[114, 114, 114]);
+    }
+
+    #[test]
+    fn test_letterbox_bilinear_upscale() {
+        // 2x1 grayscale gradient upscaled to 4x2.
+        let input = vec![0u8, 100];
+        let (out, _) = letterbox(&input, 2, 1, 1, 4, 2, [0, 0, 0]);
+        assert_eq!(&out[0..4], &[0, 25, 75, 100]);
+        assert_eq!(&out[4..8], &[0, 25, 75, 100]);
+    }
+
+    #[test]
+    fn test_empty_image_is_all_padding() {
+        let (out, info) = letterbox(&[], 0, 0, 3, 2, 2, [114, 114, 114]);
+        assert_eq!(out, vec![114u8; 2 * 2 * 3]);
+        assert_eq!(info, LetterboxInfo::identity(0, 0));
+
+        let (data, _) = preprocess_image(&[], 0, 4, 3, 2, 2, &PreprocessConfig::default());
+        assert_eq!(data.len(), 3 * 2 * 2);
+    }
+
+    #[test]
+    fn test_hwc_to_chw_normalized() {
+        let input = vec![255u8, 0, 51, 0, 255, 102];
+        let data = hwc_to_chw_normalized(&input, 2, 1, 3, [0.0; 3], [1.0, 1.0, 0.5]);
+        assert_eq!(data.len(), 6);
+        assert!((data[0] - 1.0).abs() < 1e-6);
+        assert!((data[1] - 0.0).abs() < 1e-6);
+        assert!((data[2] - 0.0).abs() < 1e-6);
+        assert!((data[3] - 1.0).abs() < 1e-6);
+        assert!((data[4] - 0.4).abs() < 1e-6);
+        assert!((data[5] - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_unmap_roundtrip() {
+        let info = letterbox_info(1920, 1080, 640, 640);
+        // Box covering (300, 300)-(600, 900) in the original image.
+        let model_box = [
+            300.0 * info.scale + info.pad_x,
+            300.0 * info.scale + info.pad_y,
+            600.0 * info.scale + info.pad_x,
+            900.0 * info.scale + info.pad_y,
+        ];
+        let bbox = info.unmap_tlbr(model_box);
+        for (got, want) in bbox.iter().zip([300.0, 300.0, 600.0, 900.0]) {
+            assert!((got - want).abs() < 1e-3);
+        }
+    }
+}
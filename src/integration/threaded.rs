@@ -0,0 +1,451 @@
+//! ThreadedPipeline for overlapping detection and tracking on separate threads.
+//!
+//! Frames are pushed into a bounded queue and picked up by a detector thread.
+//! Detections are handed to a tracker thread, so detection of frame N+1 runs
+//! while frame N is being tracked. Results come out in submission order.
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::tracker::{BYTETracker, Detection, STrack, TrackerConfig};
+
+use super::{DetectionSource, OwnedImage};
+
+/// What to do when a frame is submitted while the queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DropPolicy {
+    /// Block the caller until the detector frees a slot.
+    #[default]
+    Block,
+    /// Discard the oldest queued frame to make room for the new one.
+    DropOldest,
+    /// Discard the newly submitted frame.
+    DropNewest,
+}
+
+/// Options for a `ThreadedPipeline`.
+#[derive(Debug, Clone)]
+pub struct PipelineOptions {
+    /// Maximum number of frames waiting for the detector.
+    pub queue_capacity: usize,
+    /// Behavior when the queue is full.
+    pub drop_policy: DropPolicy,
+}
+
+impl Default for PipelineOptions {
+    fn default() -> Self {
+        Self {
+            queue_capacity: 4,
+            drop_policy: DropPolicy::Block,
+        }
+    }
+}
+
+/// Tracking result for one submitted frame.
+#[derive(Debug)]
+pub struct FrameOutput<E> {
+    /// Index returned by `submit` for this frame.
+    pub frame_index: u64,
+    /// Active tracks, or the detection error for this frame.
+    pub result: Result<Vec<STrack>, E>,
+}
+
+struct QueueState {
+    frames: VecDeque<(u64, OwnedImage)>,
+    closed: bool,
+    dropped: u64,
+}
+
+/// Bounded frame queue shared between the caller and the detector thread.
+struct FrameQueue {
+    state: Mutex<QueueState>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    policy: DropPolicy,
+}
+
+impl FrameQueue {
+    fn new(capacity: usize, policy: DropPolicy) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                frames: VecDeque::with_capacity(capacity),
+                closed: false,
+                dropped: 0,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity: capacity.max(1),
+            policy,
+        }
+    }
+
+    /// Push a frame, applying the drop policy. Returns whether it was queued.
+    fn push(&self, index: u64, image: OwnedImage) -> bool {
+        let mut state = self.state.lock().unwrap();
+        while state.frames.len() >= self.capacity && !state.closed {
+            match self.policy {
+                DropPolicy::Block => state = self.not_full.wait(state).unwrap(),
+                DropPolicy::DropOldest => {
+                    state.frames.pop_front();
+                    state.dropped += 1;
+                }
+                DropPolicy::DropNewest => {
+                    state.dropped += 1;
+                    return false;
+                }
+            }
+        }
+        if state.closed {
+            return false;
+        }
+        state.frames.push_back((index, image));
+        self.not_empty.notify_one();
+        true
+    }
+
+    /// Pop the next frame, blocking until one is available or the queue is closed.
+    fn pop(&self) -> Option<(u64, OwnedImage)> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(frame) = state.frames.pop_front() {
+                self.not_full.notify_one();
+                return Some(frame);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self.not_empty.wait(state).unwrap();
+        }
+    }
+
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    fn dropped(&self) -> u64 {
+        self.state.lock().unwrap().dropped
+    }
+}
+
+/// A tracking pipeline that runs detection and tracking on background threads.
+///
+/// Detection and tracking overlap: while the tracker thread updates
+/// `BYTETracker` with the detections of one frame, the detector thread is
+/// already processing the next. Frames dropped by the `DropPolicy` produce no
+/// output; all other frames are reported in submission order.
+pub struct ThreadedPipeline<D>
+where
+    D: DetectionSource + Send + 'static,
+    D::Error: Send + 'static,
+{
+    queue: Arc<FrameQueue>,
+    results: Option<Receiver<FrameOutput<D::Error>>>,
+    detector_thread: Option<JoinHandle<D>>,
+    tracker_thread: Option<JoinHandle<BYTETracker>>,
+    next_index: u64,
+}
+
+impl<D> ThreadedPipeline<D>
+where
+    D: DetectionSource + Send + 'static,
+    D::Error: Send + 'static,
+{
+    /// Start the detector and tracker threads.
+    pub fn spawn(detector: D, config: TrackerConfig, options: PipelineOptions) -> Self {
+        let queue = Arc::new(FrameQueue::new(options.queue_capacity, options.drop_policy));
+        let (det_tx, det_rx) = mpsc::sync_channel(1);
+        let (out_tx, out_rx) = mpsc::channel();
+
+        let detector_queue = Arc::clone(&queue);
+        let detector_thread =
+            thread::spawn(move || run_detector(detector, &detector_queue, det_tx));
+        let tracker_thread = thread::spawn(move || {
+            let mut tracker = BYTETracker::new(config);
+            for (frame_index, detections) in det_rx {
+                let result = detections.map(|dets: Vec<Detection>| tracker.update(dets));
+                if out_tx
+                    .send(FrameOutput {
+                        frame_index,
+                        result,
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            tracker
+        });
+
+        Self {
+            queue,
+            results: Some(out_rx),
+            detector_thread: Some(detector_thread),
+            tracker_thread: Some(tracker_thread),
+            next_index: 0,
+        }
+    }
+
+    /// Submit a frame for processing.
+    ///
+    /// # Returns
+    /// The frame index that will identify its output, or `None` if the frame
+    /// was dropped (`DropPolicy::DropNewest` on a full queue).
+    pub fn submit(&mut self, image: OwnedImage) -> Option<u64> {
+        let index = self.next_index;
+        self.next_index += 1;
+        self.queue.push(index, image).then_some(index)
+    }
+
+    /// Stop accepting new frames.
+    ///
+    /// Frames already queued are still processed; once their results have
+    /// been received, `recv` returns `None`.
+    pub fn close(&self) {
+        self.queue.close();
+    }
+
+    /// Wait for the next result.
+    ///
+    /// Returns `None` once the pipeline is closed and drained, or if the
+    /// results are being delivered through a stream.
+    pub fn recv(&self) -> Option<FrameOutput<D::Error>> {
+        self.results.as_ref()?.recv().ok()
+    }
+
+    /// Get the next result if one is ready.
+    pub fn try_recv(&self) -> Option<FrameOutput<D::Error>> {
+        self.results.as_ref()?.try_recv().ok()
+    }
+
+    /// Number of frames discarded by the drop policy so far.
+    pub fn dropped_frames(&self) -> u64 {
+        self.queue.dropped()
+    }
+
+    /// Stop accepting frames, process everything already queued and return
+    /// all remaining results along with the detector and tracker.
+    pub fn finish(mut self) -> (Vec<FrameOutput<D::Error>>, D, BYTETracker) {
+        self.queue.close();
+        let detector = join(self.detector_thread.take());
+        let tracker = join(self.tracker_thread.take());
+        let remaining = self
+            .results
+            .take()
+            .map(|rx| rx.into_iter().collect())
+            .unwrap_or_default();
+        (remaining, detector, tracker)
+    }
+}
+
+impl<D> Drop for ThreadedPipeline<D>
+where
+    D: DetectionSource + Send + 'static,
+    D::Error: Send + 'static,
+{
+    fn drop(&mut self) {
+        self.queue.close();
+        if let Some(handle) = self.detector_thread.take() {
+            let _ = handle.join();
+        }
+        if let Some(handle) = self.tracker_thread.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+type DetectionResult<E> = (u64, Result<Vec<Detection>, E>);
+
+fn run_detector<D: DetectionSource>(
+    mut detector: D,
+    queue: &FrameQueue,
+    tx: SyncSender<DetectionResult<D::Error>>,
+) -> D {
+    while let Some((index, image)) = queue.pop() {
+        let result = detector.detect_image(&image.view());
+        if tx.send((index, result)).is_err() {
+            break;
+        }
+    }
+    detector
+}
+
+fn join<T>(handle: Option<JoinHandle<T>>) -> T {
+    match handle.expect("pipeline thread already joined").join() {
+        Ok(value) => value,
+        Err(panic) => std::panic::resume_unwind(panic),
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod stream {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use tokio::sync::mpsc::UnboundedReceiver;
+    use tokio_stream::Stream;
+
+    use super::{DetectionSource, FrameOutput, ThreadedPipeline};
+
+    /// Async `Stream` of tracking results from a `ThreadedPipeline`.
+    pub struct FrameStream<E> {
+        rx: UnboundedReceiver<FrameOutput<E>>,
+    }
+
+    impl<E> Stream for FrameStream<E> {
+        type Item = FrameOutput<E>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.rx.poll_recv(cx)
+        }
+    }
+
+    impl<D> ThreadedPipeline<D>
+    where
+        D: DetectionSource + Send + 'static,
+        D::Error: Send + 'static,
+    {
+        /// Take the results as an async `Stream`.
+        ///
+        /// After this call `recv`, `try_recv` and `finish` no longer return
+        /// results; they are delivered through the stream instead. The stream
+        /// ends once the pipeline is finished or dropped.
+        pub fn result_stream(&mut self) -> FrameStream<D::Error> {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            if let Some(results) = self.results.take() {
+                std::thread::spawn(move || {
+                    for output in results {
+                        if tx.send(output).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            FrameStream { rx }
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub use stream::FrameStream;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::ImageView;
+
+    /// Detector that reports its start and waits for a gate before returning.
+    struct GatedDetector {
+        started: mpsc::Sender<u32>,
+        gate: mpsc::Receiver<()>,
+    }
+
+    impl DetectionSource for GatedDetector {
+        type Error = String;
+
+        fn detect_image(&mut self, image: &ImageView<'_>) -> Result<Vec<Detection>, Self::Error> {
+            self.started.send(image.width()).unwrap();
+            self.gate.recv().map_err(|e| e.to_string())?;
+            let x = image.width() as f32;
+            Ok(vec![Detection::new(x, 10.0, x + 40.0, 90.0, 0.9)])
+        }
+    }
+
+    fn frame(width: u32) -> OwnedImage {
+        OwnedImage::rgb(vec![0; width as usize * 3], width, 1)
+    }
+
+    fn gated(
+        policy: DropPolicy,
+    ) -> (
+        ThreadedPipeline<GatedDetector>,
+        mpsc::Receiver<u32>,
+        mpsc::Sender<()>,
+    ) {
+        let (started_tx, started_rx) = mpsc::channel();
+        let (gate_tx, gate_rx) = mpsc::channel();
+        let detector = GatedDetector {
+            started: started_tx,
+            gate: gate_rx,
+        };
+        let options = PipelineOptions {
+            queue_capacity: 1,
+            drop_policy: policy,
+        };
+        let pipeline = ThreadedPipeline::spawn(detector, TrackerConfig::default(), options);
+        (pipeline, started_rx, gate_tx)
+    }
+
+    #[test]
+    fn test_threaded_ordered_output() {
+        let (mut pipeline, _started, gate) = gated(DropPolicy::Block);
+        for _ in 0..3 {
+            gate.send(()).unwrap();
+        }
+        for w in [100, 110, 120] {
+            pipeline.submit(frame(w));
+        }
+
+        let (outputs, _, tracker) = pipeline.finish();
+        let indices: Vec<u64> = outputs.iter().map(|o| o.frame_index).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+        assert!(outputs.iter().all(|o| o.result.is_ok()));
+        assert_eq!(tracker.frame_id(), 3);
+    }
+
+    #[test]
+    fn test_threaded_drop_oldest() {
+        let (mut pipeline, started, gate) = gated(DropPolicy::DropOldest);
+        pipeline.submit(frame(100));
+        assert_eq!(started.recv().unwrap(), 100);
+
+        // Detector is busy: frame 1 is queued, then replaced by frame 2.
+        assert_eq!(pipeline.submit(frame(110)), Some(1));
+        assert_eq!(pipeline.submit(frame(120)), Some(2));
+        assert_eq!(pipeline.dropped_frames(), 1);
+
+        gate.send(()).unwrap();
+        gate.send(()).unwrap();
+        let (outputs, ..) = pipeline.finish();
+        let indices: Vec<u64> = outputs.iter().map(|o| o.frame_index).collect();
+        assert_eq!(indices, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_threaded_drop_newest() {
+        let (mut pipeline, started, gate) = gated(DropPolicy::DropNewest);
+        pipeline.submit(frame(100));
+        assert_eq!(started.recv().unwrap(), 100);
+
+        assert_eq!(pipeline.submit(frame(110)), Some(1));
+        assert_eq!(pipeline.submit(frame(120)), None);
+        assert_eq!(pipeline.dropped_frames(), 1);
+
+        gate.send(()).unwrap();
+        gate.send(()).unwrap();
+        let (outputs, ..) = pipeline.finish();
+        let indices: Vec<u64> = outputs.iter().map(|o| o.frame_index).collect();
+        assert_eq!(indices, vec![0, 1]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_threaded_result_stream() {
+        use tokio_stream::StreamExt;
+
+        let (mut pipeline, _started, gate) = gated(DropPolicy::Block);
+        let stream = pipeline.result_stream();
+        gate.send(()).unwrap();
+        gate.send(()).unwrap();
+        pipeline.submit(frame(100));
+        pipeline.submit(frame(110));
+        drop(pipeline);
+
+        let indices: Vec<u64> = stream.map(|o| o.frame_index).collect().await;
+        assert_eq!(indices, vec![0, 1]);
+    }
+}
@@ -0,0 +1,231 @@
+//! ONNX inference backend for object detection, powered by tract.
+//!
+//! This module provides a `TractDetector` that implements `DetectionSource`
+//! for running ONNX detection models on the CPU in pure Rust. Preprocessing
+//! (letterbox, normalization) and postprocessing (decoding, NMS) are shared
+//! with the Burn backend.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use bytetrack_rs::{TractDetector, TrackerPipeline, YoloDecoder, YoloVersion};
+//!
+//! let decoder = YoloDecoder::new(YoloVersion::V8, 80);
+//! let detector = TractDetector::from_path("yolov8n.onnx", decoder)?;
+//! let mut pipeline = TrackerPipeline::with_default_config(detector);
+//! ```
+
+use std::path::Path;
+use std::sync::Arc;
+
+use tract_onnx::prelude::*;
+
+use super::decode::YoloDecoder;
+use super::postprocess::{PostprocessConfig, postprocess_detections};
+use super::preprocess::{LetterboxInfo, PreprocessConfig, preprocess_image};
+use super::{DetectionSource, ImageError, ImageView};
+use crate::tracker::Detection;
+
+/// Error type for tract detection failures.
+#[derive(Debug, Clone)]
+pub enum TractDetectorError {
+    /// The ONNX model could not be loaded or optimized.
+    ModelLoadError(String),
+    /// Input image is malformed or can't be converted for the model.
+    InvalidImage(ImageError),
+    /// Preprocessing failed.
+    PreprocessingError(String),
+    /// Model inference failed.
+    InferenceError(String),
+}
+
+impl std::fmt::Display for TractDetectorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ModelLoadError(msg) => write!(f, "Model load error: {}", msg),
+            Self::InvalidImage(err) => write!(f, "Invalid image: {}", err),
+            Self::PreprocessingError(msg) => write!(f, "Preprocessing error: {}", msg),
+            Self::InferenceError(msg) => write!(f, "Inference error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TractDetectorError {}
+
+/// ONNX object detector implementing `DetectionSource`.
+///
+/// The model is expected to take a single `[1, C, H, W]` float input and
+/// produce a YOLO-style output tensor, which is decoded with the given
+/// `YoloDecoder`.
+pub struct TractDetector {
+    plan: Arc<TypedRunnableModel>,
+    input_size: (u32, u32, u32),
+    decoder: YoloDecoder,
+    preprocess_config: PreprocessConfig,
+    postprocess_config: PostprocessConfig,
+}
+
+impl TractDetector {
+    /// Load an ONNX model whose input shape is fully specified in the file.
+    pub fn from_path(
+        path: impl AsRef<Path>,
+        decoder: YoloDecoder,
+    ) -> Result<Self, TractDetectorError> {
+        let model = tract_onnx::onnx()
+            .model_for_path(path)
+            .and_then(|m| m.into_typed())
+            .map_err(|e| TractDetectorError::ModelLoadError(e.to_string()))?;
+
+        let shape = model
+            .input_fact(0)
+            .ok()
+            .and_then(|f| f.shape.as_concrete().map(|s| s.to_vec()))
+            .ok_or_else(|| {
+                TractDetectorError::ModelLoadError(
+                    "Model input shape is not fully specified; use from_path_with_input_size"
+                        .to_string(),
+                )
+            })?;
+        let [_, c, h, w] = shape[..] else {
+            return Err(TractDetectorError::ModelLoadError(format!(
+                "Expected a 4D model input, got shape {:?}",
+                shape
+            )));
+        };
+
+        Self::from_typed(model, (c as u32, h as u32, w as u32), decoder)
+    }
+
+    /// Load an ONNX model, fixing its input to `[1, channels, height, width]`.
+    ///
+    /// Use this for models exported with dynamic input dimensions.
+    pub fn from_path_with_input_size(
+        path: impl AsRef<Path>,
+        input_size: (u32, u32, u32),
+        decoder: YoloDecoder,
+    ) -> Result<Self, TractDetectorError> {
+        let (c, h, w) = input_size;
+        let model = tract_onnx::onnx()
+            .model_for_path(path)
+            .and_then(|m| {
+                m.with_input_fact(0, f32::fact([1, c as usize, h as usize, w as usize]).into())
+            })
+            .and_then(|m| m.into_typed())
+            .map_err(|e| TractDetectorError::ModelLoadError(e.to_string()))?;
+
+        Self::from_typed(model, input_size, decoder)
+    }
+
+    fn from_typed(
+        model: TypedModel,
+        input_size: (u32, u32, u32),
+        decoder: YoloDecoder,
+    ) -> Result<Self, TractDetectorError> {
+        let plan = model
+            .into_optimized()
+            .and_then(|m| m.into_runnable())
+            .map_err(|e| TractDetectorError::ModelLoadError(e.to_string()))?;
+
+        let (_, h, w) = input_size;
+        Ok(Self {
+            plan,
+            input_size,
+            decoder: decoder.with_input_size(w, h),
+            preprocess_config: PreprocessConfig::default(),
+            postprocess_config: PostprocessConfig::default(),
+        })
+    }
+
+    /// Set the confidence threshold for filtering detections.
+    pub fn with_conf_threshold(mut self, threshold: f32) -> Self {
+        self.postprocess_config.conf_threshold = threshold;
+        self
+    }
+
+    /// Set the IoU threshold for non-maximum suppression, or `None` to disable NMS.
+    pub fn with_nms_threshold(mut self, threshold: Option<f32>) -> Self {
+        self.postprocess_config.nms_threshold = threshold;
+        self
+    }
+
+    /// Suppress overlapping boxes across classes instead of per class.
+    pub fn with_class_agnostic_nms(mut self, agnostic: bool) -> Self {
+        self.postprocess_config.class_agnostic_nms = agnostic;
+        self
+    }
+
+    /// Set the color used to pad letterboxed frames.
+    pub fn with_pad_color(mut self, color: [u8; 3]) -> Self {
+        self.preprocess_config.pad_color = color;
+        self
+    }
+
+    /// Set the per-channel mean and standard deviation used for normalization.
+    ///
+    /// Pixels are scaled to `[0, 1]` before `(x - mean) / std` is applied.
+    pub fn with_normalization(mut self, mean: [f32; 3], std: [f32; 3]) -> Self {
+        self.preprocess_config.mean = mean;
+        self.preprocess_config.std = std;
+        self
+    }
+
+    /// Get the model input size (channels, height, width).
+    pub fn input_size(&self) -> (u32, u32, u32) {
+        self.input_size
+    }
+
+    /// Preprocess an image to a tract tensor.
+    pub fn preprocess(
+        &self,
+        image: &ImageView<'_>,
+    ) -> Result<(Tensor, LetterboxInfo), TractDetectorError> {
+        let (channels, target_h, target_w) = self.input_size;
+
+        let pixels = image
+            .to_packed(channels)
+            .map_err(TractDetectorError::InvalidImage)?;
+
+        let (data, letterbox) = preprocess_image(
+            &pixels,
+            image.width(),
+            image.height(),
+            channels,
+            target_w,
+            target_h,
+            &self.preprocess_config,
+        );
+
+        let shape = [1, channels as usize, target_h as usize, target_w as usize];
+        let tensor = Tensor::from_shape(&shape, &data)
+            .map_err(|e| TractDetectorError::PreprocessingError(e.to_string()))?;
+
+        Ok((tensor, letterbox))
+    }
+}
+
+impl DetectionSource for TractDetector {
+    type Error = TractDetectorError;
+
+    fn detect_image(&mut self, image: &ImageView<'_>) -> Result<Vec<Detection>, Self::Error> {
+        let (tensor, letterbox) = self.preprocess(image)?;
+
+        let outputs = self
+            .plan
+            .run(tvec!(tensor.into()))
+            .map_err(|e| TractDetectorError::InferenceError(e.to_string()))?;
+        let output: Vec<f32> = outputs[0]
+            .to_plain_array_view::<f32>()
+            .map_err(|e| TractDetectorError::InferenceError(e.to_string()))?
+            .iter()
+            .copied()
+            .collect();
+
+        let raw_detections = self.decoder.decode(&output);
+        Ok(postprocess_detections(
+            raw_detections,
+            true,
+            &letterbox,
+            &self.postprocess_config,
+        ))
+    }
+}
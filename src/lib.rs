@@ -1,6 +1,10 @@
+pub mod analytics;
 pub mod tracker;
 
-pub use tracker::{BYTETracker, Detection, Rect, STrack, TrackState, TrackerConfig};
+pub use tracker::{
+    AssignmentError, AssignmentMethod, BYTETracker, Detection, Rect, STrack, TrackState,
+    TrackerConfig, TrackerStats,
+};
 
 mod integration;
 pub use integration::*;
@@ -1,12 +1,46 @@
+mod assignment;
+mod box3d;
 mod byte_tracker;
+mod cross_camera;
+mod geometry;
+mod ground;
+mod history;
 mod kalman_filter;
+mod keypoints;
+mod mask;
 mod matching;
+mod motion;
+mod polygon;
+mod pool;
 mod rect;
+mod region;
+mod rotated_rect;
+mod sparse;
+mod stats;
 mod strack;
 mod track_state;
 
+pub use assignment::{
+    AssignmentError, AssignmentMethod, AssignmentSolver, AuctionSolver, GreedySolver,
+    HungarianSolver, LapjvSolver,
+};
+pub use box3d::Box3D;
 pub use byte_tracker::{BYTETracker, TrackerConfig};
+pub use cross_camera::{
+    CameraTopology, CrossCameraAssociator, CrossCameraConfig, Tracklet, TravelWindow,
+};
+pub use geometry::{Affinity, BoxGeometry};
+pub use ground::{CalibrationError, GroundCalibration, GroundPlaneConfig, Homography};
+pub use history::{TrajectoryHistory, TrajectoryPoint};
+pub use keypoints::{COCO_KEYPOINT_SIGMAS, Keypoint, KeypointConfig, oks, smooth_keypoints};
+pub use mask::{MaskError, RleMask};
 pub use matching::Detection;
+pub use motion::{Forecast, UncertaintyEllipse, Velocity, time_to_collision};
+pub use polygon::{Anchor, Polygon};
+pub use pool::{PoolStats, TrackerPool};
 pub use rect::Rect;
+pub use region::RegionFilter;
+pub use rotated_rect::RotatedRect;
+pub use stats::TrackerStats;
 pub use strack::{STrack, reset_track_id_counter};
 pub use track_state::TrackState;
@@ -0,0 +1,357 @@
+//! Linear assignment solvers used to match tracks with detections.
+
+use ndarray::Array2;
+
+/// Error type for assignment failures.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssignmentError {
+    /// The cost matrix contains a NaN or infinite value.
+    InvalidCost { row: usize, col: usize, value: f32 },
+    /// The underlying solver reported an error.
+    SolverFailed(String),
+    /// An iterative solver hit its iteration limit.
+    NotConverged { iterations: usize },
+}
+
+impl std::fmt::Display for AssignmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidCost { row, col, value } => {
+                write!(f, "Invalid cost {} at ({}, {})", value, row, col)
+            }
+            Self::SolverFailed(msg) => write!(f, "Assignment solver failed: {}", msg),
+            Self::NotConverged { iterations } => {
+                write!(
+                    f,
+                    "Assignment did not converge after {} iterations",
+                    iterations
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for AssignmentError {}
+
+/// A solver for the rectangular linear assignment problem.
+///
+/// Given an `M x N` matrix of finite costs, return for every row the column
+/// it's assigned to, or `None`. Each column is used at most once and
+/// `min(M, N)` rows are assigned. Thresholding is left to the caller.
+pub trait AssignmentSolver {
+    fn solve(&self, cost_matrix: &Array2<f32>) -> Result<Vec<Option<usize>>, AssignmentError>;
+}
+
+/// Jonker-Volgenant solver, padding the matrix to a square.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LapjvSolver;
+
+impl AssignmentSolver for LapjvSolver {
+    fn solve(&self, cost_matrix: &Array2<f32>) -> Result<Vec<Option<usize>>, AssignmentError> {
+        let (num_rows, num_cols) = cost_matrix.dim();
+        let size = num_rows.max(num_cols);
+        let mut padded = Array2::<f64>::from_elem((size, size), 1e6);
+        for ((i, j), &cost) in cost_matrix.indexed_iter() {
+            padded[[i, j]] = cost as f64;
+        }
+
+        let (row_to_col, _) =
+            lapjv::lapjv(&padded).map_err(|e| AssignmentError::SolverFailed(e.to_string()))?;
+        Ok(row_to_col
+            .into_iter()
+            .take(num_rows)
+            .map(|col| (col < num_cols).then_some(col))
+            .collect())
+    }
+}
+
+/// Hungarian (Kuhn-Munkres) solver working directly on rectangular matrices.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HungarianSolver;
+
+impl AssignmentSolver for HungarianSolver {
+    fn solve(&self, cost_matrix: &Array2<f32>) -> Result<Vec<Option<usize>>, AssignmentError> {
+        let (num_rows, num_cols) = cost_matrix.dim();
+        if num_rows > num_cols {
+            let by_col = hungarian(&cost_matrix.t().to_owned());
+            return Ok(invert(&by_col, num_rows));
+        }
+        Ok(hungarian(cost_matrix))
+    }
+}
+
+/// Shortest augmenting path Hungarian algorithm for `rows <= cols`.
+fn hungarian(cost: &Array2<f32>) -> Vec<Option<usize>> {
+    let (n, m) = cost.dim();
+    // 1-based potentials and column owners, with index 0 as a sentinel.
+    let mut u = vec![0.0f64; n + 1];
+    let mut v = vec![0.0f64; m + 1];
+    let mut owner = vec![0usize; m + 1];
+    let mut way = vec![0usize; m + 1];
+
+    for i in 1..=n {
+        owner[0] = i;
+        let mut j0 = 0;
+        let mut min_v = vec![f64::INFINITY; m + 1];
+        let mut used = vec![false; m + 1];
+        loop {
+            used[j0] = true;
+            let i0 = owner[j0];
+            let mut delta = f64::INFINITY;
+            let mut j1 = 0;
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let cur = cost[[i0 - 1, j - 1]] as f64 - u[i0] - v[j];
+                if cur < min_v[j] {
+                    min_v[j] = cur;
+                    way[j] = j0;
+                }
+                if min_v[j] < delta {
+                    delta = min_v[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    u[owner[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_v[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if owner[j0] == 0 {
+                break;
+            }
+        }
+        while j0 != 0 {
+            let j1 = way[j0];
+            owner[j0] = owner[j1];
+            j0 = j1;
+        }
+    }
+
+    let mut row_to_col = vec![None; n];
+    for j in 1..=m {
+        if owner[j] != 0 {
+            row_to_col[owner[j] - 1] = Some(j - 1);
+        }
+    }
+    row_to_col
+}
+
+/// Greedy solver: repeatedly takes the cheapest remaining pair.
+///
+/// Fast but approximate; it can miss the optimal assignment when boxes
+/// compete for the same detection.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GreedySolver;
+
+impl AssignmentSolver for GreedySolver {
+    fn solve(&self, cost_matrix: &Array2<f32>) -> Result<Vec<Option<usize>>, AssignmentError> {
+        let (num_rows, num_cols) = cost_matrix.dim();
+        let mut pairs: Vec<(f32, usize, usize)> = cost_matrix
+            .indexed_iter()
+            .map(|((i, j), &cost)| (cost, i, j))
+            .collect();
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+
+        let mut row_to_col = vec![None; num_rows];
+        let mut col_used = vec![false; num_cols];
+        for (_, i, j) in pairs {
+            if row_to_col[i].is_none() && !col_used[j] {
+                row_to_col[i] = Some(j);
+                col_used[j] = true;
+            }
+        }
+        Ok(row_to_col)
+    }
+}
+
+/// Forward auction solver for rectangular matrices, without square padding.
+///
+/// The total cost of the result is within `min(M, N) * epsilon` of the
+/// optimum, where `epsilon` is `epsilon_scale` times the cost range.
+#[derive(Debug, Clone, Copy)]
+pub struct AuctionSolver {
+    /// Bid increment relative to the range of costs in the matrix.
+    pub epsilon_scale: f64,
+    /// Maximum number of bids before giving up.
+    pub max_iterations: usize,
+}
+
+impl Default for AuctionSolver {
+    fn default() -> Self {
+        Self {
+            epsilon_scale: 1e-4,
+            max_iterations: 10_000_000,
+        }
+    }
+}
+
+impl AssignmentSolver for AuctionSolver {
+    fn solve(&self, cost_matrix: &Array2<f32>) -> Result<Vec<Option<usize>>, AssignmentError> {
+        let (num_rows, num_cols) = cost_matrix.dim();
+        if num_rows > num_cols {
+            let by_col = self.auction(&cost_matrix.t().to_owned())?;
+            return Ok(invert(&by_col, num_rows));
+        }
+        self.auction(cost_matrix)
+    }
+}
+
+impl AuctionSolver {
+    /// Rows bid for columns until every row is assigned. Requires `rows <= cols`.
+    fn auction(&self, cost: &Array2<f32>) -> Result<Vec<Option<usize>>, AssignmentError> {
+        let (n, m) = cost.dim();
+        let (lo, hi) = cost
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &c| {
+                (lo.min(c as f64), hi.max(c as f64))
+            });
+        let epsilon = ((hi - lo) * self.epsilon_scale).max(1e-9);
+
+        let mut prices = vec![0.0f64; m];
+        let mut row_to_col: Vec<Option<usize>> = vec![None; n];
+        let mut col_to_row: Vec<Option<usize>> = vec![None; m];
+        let mut unassigned: Vec<usize> = (0..n).rev().collect();
+        let mut iterations = 0;
+
+        while let Some(i) = unassigned.pop() {
+            iterations += 1;
+            if iterations > self.max_iterations {
+                return Err(AssignmentError::NotConverged {
+                    iterations: self.max_iterations,
+                });
+            }
+
+            // Best and second-best net value (-cost - price) for this row.
+            let mut best = (usize::MAX, f64::NEG_INFINITY);
+            let mut second = f64::NEG_INFINITY;
+            for j in 0..m {
+                let value = -(cost[[i, j]] as f64) - prices[j];
+                if value > best.1 {
+                    second = best.1;
+                    best = (j, value);
+                } else if value > second {
+                    second = value;
+                }
+            }
+            let (j, value) = best;
+            let increment = if second.is_finite() {
+                value - second
+            } else {
+                0.0
+            };
+            prices[j] += increment + epsilon;
+
+            if let Some(previous) = col_to_row[j].replace(i) {
+                row_to_col[previous] = None;
+                unassigned.push(previous);
+            }
+            row_to_col[i] = Some(j);
+        }
+        Ok(row_to_col)
+    }
+}
+
+/// Turn a column-to-row assignment into a row-to-column one.
+fn invert(col_to_row: &[Option<usize>], num_rows: usize) -> Vec<Option<usize>> {
+    let mut row_to_col = vec![None; num_rows];
+    for (j, row) in col_to_row.iter().enumerate() {
+        if let Some(i) = row {
+            row_to_col[*i] = Some(j);
+        }
+    }
+    row_to_col
+}
+
+/// Assignment solver selectable in `TrackerConfig`.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum AssignmentMethod {
+    /// Jonker-Volgenant (the ByteTrack default).
+    #[default]
+    Lapjv,
+    /// Hungarian (Kuhn-Munkres).
+    Hungarian,
+    /// Greedy lowest-cost-first matching.
+    Greedy,
+    /// Forward auction.
+    Auction(AuctionSolver),
+}
+
+impl AssignmentSolver for AssignmentMethod {
+    fn solve(&self, cost_matrix: &Array2<f32>) -> Result<Vec<Option<usize>>, AssignmentError> {
+        match self {
+            Self::Lapjv => LapjvSolver.solve(cost_matrix),
+            Self::Hungarian => HungarianSolver.solve(cost_matrix),
+            Self::Greedy => GreedySolver.solve(cost_matrix),
+            Self::Auction(solver) => solver.solve(cost_matrix),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    fn total_cost(cost: &Array2<f32>, assignment: &[Option<usize>]) -> f32 {
+        assignment
+            .iter()
+            .enumerate()
+            .filter_map(|(i, j)| j.map(|j| cost[[i, j]]))
+            .sum()
+    }
+
+    fn pseudo_random_matrix(seed: u64, rows: usize, cols: usize) -> Array2<f32> {
+        let mut state = seed;
+        Array2::from_shape_fn((rows, cols), |_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 40) as f32 / (1u64 << 24) as f32
+        })
+    }
+
+    #[test]
+    fn test_exact_solvers_agree() {
+        for (seed, (rows, cols)) in [(5, 5), (4, 9), (9, 4), (20, 17)].into_iter().enumerate() {
+            let cost = pseudo_random_matrix(seed as u64, rows, cols);
+            let lapjv = LapjvSolver.solve(&cost).unwrap();
+            let hungarian = HungarianSolver.solve(&cost).unwrap();
+            let auction = AuctionSolver::default().solve(&cost).unwrap();
+
+            assert_eq!(lapjv.iter().flatten().count(), rows.min(cols));
+            assert_eq!(lapjv, hungarian);
+            let optimum = total_cost(&cost, &lapjv);
+            assert!(total_cost(&cost, &auction) - optimum < rows.min(cols) as f32 * 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_greedy_is_approximate() {
+        // Greedy takes (0, 0) first and forces (1, 1); optimal is the anti-diagonal.
+        let cost = array![[0.1, 0.2], [0.3, 0.9]];
+        assert_eq!(GreedySolver.solve(&cost).unwrap(), vec![Some(0), Some(1)]);
+        assert_eq!(
+            HungarianSolver.solve(&cost).unwrap(),
+            vec![Some(1), Some(0)]
+        );
+    }
+
+    #[test]
+    fn test_auction_iteration_limit() {
+        let solver = AuctionSolver {
+            max_iterations: 1,
+            ..AuctionSolver::default()
+        };
+        let cost = array![[0.0, 0.0], [0.0, 0.0]];
+        assert_eq!(
+            solver.solve(&cost),
+            Err(AssignmentError::NotConverged { iterations: 1 })
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! 3D bounding boxes from LiDAR or multi-view detectors.
+
+use std::f32::consts::PI;
+
+use ndarray::Array1;
+
+use crate::tracker::geometry::BoxGeometry;
+use crate::tracker::rect::Rect;
+use crate::tracker::rotated_rect::RotatedRect;
+
+/// Weight of the position noise relative to the box footprint.
+const STD_WEIGHT_POSITION: f64 = 1.0 / 20.0;
+/// Weight of the velocity noise relative to the box footprint.
+const STD_WEIGHT_VELOCITY: f64 = 1.0 / 160.0;
+/// Process noise of the box dimensions, which shouldn't change.
+const STD_SIZE: f64 = 1e-2;
+/// Yaw noise in radians.
+const STD_YAW: f64 = 0.05;
+/// Yaw rate noise in radians per frame.
+const STD_YAW_RATE: f64 = 0.005;
+
+/// Box in 3D space, rotated about the vertical axis.
+///
+/// `(x, y)` span the ground plane and `z` points up; `z` is the height of
+/// the box center. `yaw` is the heading in radians, measured from the x axis
+/// towards the y axis and kept in `[-pi, pi)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Box3D {
+    /// Center x coordinate
+    pub x: f32,
+    /// Center y coordinate
+    pub y: f32,
+    /// Center z coordinate
+    pub z: f32,
+    /// Extent along the heading
+    pub length: f32,
+    /// Extent across the heading
+    pub width: f32,
+    /// Vertical extent
+    pub height: f32,
+    /// Heading in radians
+    pub yaw: f32,
+}
+
+impl Box3D {
+    /// Create a box, normalizing `yaw` into `[-pi, pi)`.
+    pub fn new(x: f32, y: f32, z: f32, length: f32, width: f32, height: f32, yaw: f32) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            length,
+            width,
+            height,
+            yaw: (yaw + PI).rem_euclid(2.0 * PI) - PI,
+        }
+    }
+
+    /// Footprint of the box on the ground plane (bird's-eye view).
+    pub fn bev(&self) -> RotatedRect {
+        RotatedRect::new(self.x, self.y, self.length, self.width, self.yaw)
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.length * self.width * self.height
+    }
+
+    /// Vertical overlap with `other`.
+    fn z_overlap(&self, other: &Box3D) -> f32 {
+        let top = (self.z + self.height / 2.0).min(other.z + other.height / 2.0);
+        let bottom = (self.z - self.height / 2.0).max(other.z - other.height / 2.0);
+        (top - bottom).max(0.0)
+    }
+
+    /// Volume intersection over union with `other`.
+    pub fn iou_3d(&self, other: &Box3D) -> f32 {
+        let dz = self.z_overlap(other);
+        if dz == 0.0 {
+            return 0.0;
+        }
+        let (a, b) = (self.bev(), other.bev());
+        if a.bounding_rect().iou(&b.bounding_rect()) == 0.0 {
+            return 0.0;
+        }
+        let inter = a.to_polygon().clip_convex(&b.to_polygon()).area() * dz;
+        let union = self.volume() + other.volume() - inter;
+        if union > 0.0 {
+            (inter / union).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Footprint intersection over union with `other`, ignoring height.
+    pub fn bev_iou(&self, other: &Box3D) -> f32 {
+        self.bev().iou(&other.bev())
+    }
+
+    /// Euclidean distance between the box centers.
+    pub fn center_distance(&self, other: &Box3D) -> f32 {
+        let (dx, dy, dz) = (self.x - other.x, self.y - other.y, self.z - other.z);
+        (dx * dx + dy * dy + dz * dz).sqrt()
+    }
+}
+
+/// 3D boxes, filtered as center, dimensions and yaw.
+///
+/// `bounding_rect` is the footprint's axis-aligned extent on the ground
+/// plane, so regions and sparse association work in ground coordinates.
+/// Yaw residuals wrap at half a turn, so a detection whose heading is
+/// flipped still updates the track's orientation smoothly.
+impl BoxGeometry for Box3D {
+    const DIM: usize = 7;
+
+    fn to_measurement(&self) -> Vec<f64> {
+        [
+            self.x,
+            self.y,
+            self.z,
+            self.length,
+            self.width,
+            self.height,
+            self.yaw,
+        ]
+        .iter()
+        .map(|&v| v as f64)
+        .collect()
+    }
+
+    fn from_state(state: &Array1<f64>) -> Self {
+        let s = |i: usize| state[i] as f32;
+        Box3D::new(s(0), s(1), s(2), s(3), s(4), s(5), s(6))
+    }
+
+    fn iou(&self, other: &Self) -> f32 {
+        self.iou_3d(other)
+    }
+
+    fn bev_iou(&self, other: &Self) -> f32 {
+        Box3D::bev_iou(self, other)
+    }
+
+    fn center_distance(&self, other: &Self) -> f32 {
+        Box3D::center_distance(self, other)
+    }
+
+    fn bounding_rect(&self) -> Rect {
+        self.bev().bounding_rect()
+    }
+
+    fn initial_std(measurement: &[f64]) -> Vec<f64> {
+        let scale = measurement[3].max(measurement[4]);
+        let (pos, vel) = (
+            2.0 * STD_WEIGHT_POSITION * scale,
+            10.0 * STD_WEIGHT_VELOCITY * scale,
+        );
+        vec![
+            pos,
+            pos,
+            pos,
+            pos,
+            pos,
+            pos,
+            2.0 * STD_YAW,
+            vel,
+            vel,
+            vel,
+            1e-5,
+            1e-5,
+            1e-5,
+            10.0 * STD_YAW_RATE,
+        ]
+    }
+
+    fn process_std(state: &Array1<f64>) -> Vec<f64> {
+        let scale = state[3].max(state[4]);
+        let (pos, vel) = (STD_WEIGHT_POSITION * scale, STD_WEIGHT_VELOCITY * scale);
+        vec![
+            pos,
+            pos,
+            pos,
+            STD_SIZE,
+            STD_SIZE,
+            STD_SIZE,
+            STD_YAW,
+            vel,
+            vel,
+            vel,
+            1e-5,
+            1e-5,
+            1e-5,
+            STD_YAW_RATE,
+        ]
+    }
+
+    fn measurement_std(state: &Array1<f64>) -> Vec<f64> {
+        let pos = STD_WEIGHT_POSITION * state[3].max(state[4]);
+        vec![pos, pos, pos, pos, pos, pos, STD_YAW]
+    }
+
+    fn wrap_residual(residual: &mut Array1<f64>) {
+        let half_turn = std::f64::consts::PI;
+        residual[6] = (residual[6] + half_turn / 2.0).rem_euclid(half_turn) - half_turn / 2.0;
+    }
+
+    fn hold_size(state: &mut Array1<f64>) {
+        for i in 10..13 {
+            state[i] = 0.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::geometry::Affinity;
+
+    #[test]
+    fn test_iou_3d() {
+        let a = Box3D::new(0.0, 0.0, 1.0, 4.0, 2.0, 2.0, 0.0);
+        assert!((a.iou_3d(&a) - 1.0).abs() < 1e-5);
+
+        // Half the footprint and half the height overlap: 4 / (16 + 16 - 4).
+        let b = Box3D::new(2.0, 0.0, 2.0, 4.0, 2.0, 2.0, 0.0);
+        assert!((a.iou_3d(&b) - 4.0 / 28.0).abs() < 1e-5);
+        assert!((a.bev_iou(&b) - 4.0 / 12.0).abs() < 1e-5);
+
+        // Stacked boxes share a footprint but no volume.
+        let above = Box3D::new(0.0, 0.0, 5.0, 4.0, 2.0, 2.0, 0.0);
+        assert_eq!(a.iou_3d(&above), 0.0);
+        assert!((a.bev_iou(&above) - 1.0).abs() < 1e-5);
+
+        // A flipped heading is the same box.
+        let flipped = Box3D::new(0.0, 0.0, 1.0, 4.0, 2.0, 2.0, PI);
+        assert!((a.iou_3d(&flipped) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_center_distance_affinity() {
+        let a = Box3D::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0);
+        let b = Box3D::new(3.0, 4.0, 0.0, 1.0, 1.0, 1.0, 0.0);
+        assert_eq!(a.center_distance(&b), 5.0);
+        let affinity = Affinity::CenterDistance { max_distance: 10.0 };
+        assert!((a.affinity(&b, affinity) - 0.5).abs() < 1e-6);
+        assert_eq!(a.affinity(&b, Affinity::Iou), 0.0);
+    }
+
+    #[test]
+    fn test_measurement_round_trip() {
+        let b = Box3D::new(1.0, 2.0, 3.0, 4.5, 1.8, 1.5, 0.4);
+        let state = Array1::from_vec(b.to_measurement());
+        assert_eq!(Box3D::from_state(&state), b);
+
+        let mut residual = Array1::zeros(7);
+        residual[6] = 3.0;
+        Box3D::wrap_residual(&mut residual);
+        assert!((residual[6] - (3.0 - std::f64::consts::PI)).abs() < 1e-9);
+    }
+}
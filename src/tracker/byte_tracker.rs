@@ -1,10 +1,19 @@
 //! Main BYTETracker algorithm implementation.
 
+use crate::tracker::assignment::{AssignmentError, AssignmentMethod};
+use crate::tracker::geometry::{Affinity, BoxGeometry};
+use crate::tracker::ground::{GroundKalmanFilter, GroundPlaneConfig};
 use crate::tracker::kalman_filter::KalmanFilter;
+use crate::tracker::keypoints::{Keypoint, KeypointConfig, oks, smooth_keypoints};
 use crate::tracker::matching::{self, AssignmentResult, Detection};
+use crate::tracker::motion::Forecast;
 use crate::tracker::rect::{Rect, iou_batch};
+use crate::tracker::region::RegionFilter;
+use crate::tracker::sparse;
+use crate::tracker::stats::{TrackerStats, stage_event, stage_span, timed};
 use crate::tracker::strack::STrack;
 use crate::tracker::track_state::TrackState;
+use std::time::Instant;
 
 /// Configuration for the BYTETracker.
 #[derive(Debug, Clone)]
@@ -13,6 +22,33 @@ pub struct TrackerConfig {
     pub match_thresh: f32,
     pub track_buffer: u32,
     pub frame_rate: f32,
+    /// Only score overlapping track/detection pairs, found with a spatial
+    /// grid, and solve each connected group separately. Gives the same
+    /// matches as the dense path and scales better to hundreds of objects.
+    pub sparse_association: bool,
+    /// Solver used for every association stage.
+    pub assignment: AssignmentMethod,
+    /// How track/detection pairs are scored in every association stage.
+    pub affinity: Affinity,
+    /// Also track foot points on the ground plane, optionally gating matches
+    /// by world distance.
+    pub ground_plane: Option<GroundPlaneConfig>,
+    /// Keep this many recent observations per track in `STrack::history`.
+    pub history_length: Option<usize>,
+    /// Drop detections outside a region of interest or inside ignore masks,
+    /// and restrict where tracks may start.
+    pub region: Option<RegionFilter>,
+    /// Lost-track buffer, in frames at 30 fps, for tracks whose predicted
+    /// center has left the image. Only used by `update_in_frame`.
+    pub out_of_frame_buffer: u32,
+    /// Weight of mask IoU in the association cost for track/detection
+    /// pairs that both have masks: `0.0` uses box IoU only, `1.0` mask IoU
+    /// only. Only pairs whose boxes overlap are scored, and a track's mask
+    /// is its last matched mask, without motion compensation.
+    pub mask_weight: f32,
+    /// Fuse Object Keypoint Similarity into the first association and
+    /// smooth each track's keypoints.
+    pub keypoints: Option<KeypointConfig>,
 }
 
 impl Default for TrackerConfig {
@@ -22,36 +58,208 @@ impl Default for TrackerConfig {
             match_thresh: 0.8,
             track_buffer: 30,
             frame_rate: 30.0,
+            sparse_association: false,
+            assignment: AssignmentMethod::default(),
+            affinity: Affinity::default(),
+            ground_plane: None,
+            history_length: None,
+            region: None,
+            out_of_frame_buffer: 5,
+            mask_weight: 0.0,
+            keypoints: None,
         }
     }
 }
 
-pub struct BYTETracker {
-    tracked_stracks: Vec<STrack>,
-    lost_stracks: Vec<STrack>,
-    removed_stracks: Vec<STrack>,
+pub struct BYTETracker<B: BoxGeometry = Rect> {
+    tracked_stracks: Vec<STrack<B>>,
+    lost_stracks: Vec<STrack<B>>,
+    removed_stracks: Vec<STrack<B>>,
     frame_id: u32,
     config: TrackerConfig,
     max_time_lost: u32,
-    kalman_filter: KalmanFilter,
+    max_time_out_of_frame: u32,
+    kalman_filter: KalmanFilter<B>,
+    ground_filter: Option<GroundKalmanFilter>,
+    stats: TrackerStats,
 }
 
-impl BYTETracker {
+impl<B: BoxGeometry> BYTETracker<B> {
     pub fn new(config: TrackerConfig) -> Self {
         let max_time_lost = (config.frame_rate / 30.0 * config.track_buffer as f32) as u32;
+        let max_time_out_of_frame =
+            (config.frame_rate / 30.0 * config.out_of_frame_buffer as f32) as u32;
         Self {
             tracked_stracks: Vec::new(),
             lost_stracks: Vec::new(),
             removed_stracks: Vec::new(),
             frame_id: 0,
+            ground_filter: config
+                .ground_plane
+                .as_ref()
+                .map(|ground| GroundKalmanFilter::new(ground, config.frame_rate)),
             config,
             max_time_lost,
+            max_time_out_of_frame,
             kalman_filter: KalmanFilter::default(),
+            stats: TrackerStats::default(),
         }
     }
 
-    pub fn update(&mut self, detections: Vec<Detection>) -> Vec<STrack> {
+    /// Cumulative timings and counters since creation or the last `reset_stats`.
+    pub fn stats(&self) -> TrackerStats {
+        self.stats.clone()
+    }
+
+    /// Reset all timings and counters to zero.
+    pub fn reset_stats(&mut self) {
+        self.stats = TrackerStats {
+            tracked: self.tracked_stracks.len(),
+            lost: self.lost_stracks.len(),
+            removed: self.removed_stracks.len(),
+            ..TrackerStats::default()
+        };
+    }
+
+    /// Number of frames processed so far.
+    pub fn frame_id(&self) -> u32 {
+        self.frame_id
+    }
+
+    /// Forecast every active track `steps` frames ahead without changing
+    /// tracker state.
+    ///
+    /// # Returns
+    /// `(track_id, forecasts)` for each active track; see `STrack::forecast`.
+    pub fn forecast(&self, steps: u32) -> Vec<(u64, Vec<Forecast<B>>)> {
+        self.tracked_stracks
+            .iter()
+            .filter(|t| t.is_activated)
+            .map(|t| (t.track_id, t.forecast(steps)))
+            .collect()
+    }
+
+    /// Advance one frame using motion prediction only, without detections.
+    ///
+    /// All tracks are propagated with the Kalman filter, but no track is
+    /// marked lost or removed. Use this on frames where the detector is
+    /// skipped.
+    ///
+    /// # Returns
+    /// The active tracks with their predicted boxes.
+    pub fn predict(&mut self) -> Vec<STrack<B>> {
+        let start = Instant::now();
         self.frame_id += 1;
+        stage_span!("bytetrack.predict", frame_id = self.frame_id);
+
+        // Mirror `update`, which only predicts confirmed and lost tracks.
+        timed(&mut self.stats.prediction_time, || {
+            for track in self.tracked_stracks.iter_mut().filter(|t| t.is_activated) {
+                track.predict(&self.kalman_filter);
+            }
+            STrack::multi_predict(&mut self.lost_stracks, &self.kalman_filter);
+            if let Some(ground) = &self.ground_filter {
+                let activated = self.tracked_stracks.iter_mut().filter(|t| t.is_activated);
+                for track in activated.chain(self.lost_stracks.iter_mut()) {
+                    track.ground_predict(ground);
+                }
+            }
+        });
+
+        self.stats.predicted_frames += 1;
+        self.stats.total_time += start.elapsed();
+
+        self.tracked_stracks
+            .iter()
+            .filter(|t| t.is_activated)
+            .cloned()
+            .collect()
+    }
+
+    /// Advance one frame with the given detections and return active tracks.
+    ///
+    /// If the assignment solver fails, the frame falls back to `predict`
+    /// so tracks coast instead of being dropped, and counts in the stats as
+    /// a predicted frame. Use `try_update` to handle solver errors yourself.
+    pub fn update(&mut self, detections: Vec<Detection<B>>) -> Vec<STrack<B>> {
+        match self.try_update(detections) {
+            Ok(tracks) => tracks,
+            Err(_) => self.predict(),
+        }
+    }
+
+    /// Like `update`, but returns assignment errors.
+    ///
+    /// On error the tracker state and stats are left as they were before
+    /// the call, apart from `TrackerStats::assignment_errors`.
+    pub fn try_update(
+        &mut self,
+        detections: Vec<Detection<B>>,
+    ) -> Result<Vec<STrack<B>>, AssignmentError> {
+        self.try_step(detections, None)
+    }
+
+    /// Like `update`, for a `width` x `height` image.
+    ///
+    /// Returned tracks carry the frame size, and `STrack::output_tlwh` gives
+    /// their box clipped to the image, including on the `predict` fallback.
+    /// Lost tracks whose predicted center has left the image are removed
+    /// after `out_of_frame_buffer` frames instead of the full `track_buffer`.
+    pub fn update_in_frame(
+        &mut self,
+        detections: Vec<Detection<B>>,
+        width: u32,
+        height: u32,
+    ) -> Vec<STrack<B>> {
+        match self.try_update_in_frame(detections, width, height) {
+            Ok(tracks) => tracks,
+            Err(_) => self
+                .predict()
+                .into_iter()
+                .map(|t| STrack {
+                    frame_size: Some((width, height)),
+                    ..t
+                })
+                .collect(),
+        }
+    }
+
+    /// Like `update_in_frame`, but returns assignment errors.
+    pub fn try_update_in_frame(
+        &mut self,
+        detections: Vec<Detection<B>>,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<STrack<B>>, AssignmentError> {
+        self.try_step(detections, Some((width, height)))
+    }
+
+    fn try_step(
+        &mut self,
+        detections: Vec<Detection<B>>,
+        frame_size: Option<(u32, u32)>,
+    ) -> Result<Vec<STrack<B>>, AssignmentError> {
+        // Counters and timings of a failed frame are rolled back.
+        let stats = self.stats.clone();
+        self.frame_id += 1;
+        let result = self.step(detections, frame_size);
+        if result.is_err() {
+            self.frame_id -= 1;
+            self.stats = stats;
+            self.stats.assignment_errors += 1;
+        }
+        result
+    }
+
+    /// Run the ByteTrack association for the current `frame_id`. Tracker
+    /// state is only modified once every association has succeeded.
+    fn step(
+        &mut self,
+        detections: Vec<Detection<B>>,
+        frame_size: Option<(u32, u32)>,
+    ) -> Result<Vec<STrack<B>>, AssignmentError> {
+        let start = Instant::now();
+        stage_span!("bytetrack.update", frame_id = self.frame_id);
 
         let mut activated_stracks = Vec::new();
         let mut refind_stracks = Vec::new();
@@ -62,6 +270,19 @@ impl BYTETracker {
         let mut remain_detections = Vec::new();
         let mut detections_low = Vec::new();
 
+        let detections = match &self.config.region {
+            Some(region) => {
+                let total = detections.len();
+                let kept: Vec<Detection<B>> = detections
+                    .into_iter()
+                    .filter(|d| region.accepts(&d.bbox.bounding_rect()))
+                    .collect();
+                self.stats.filtered_detections += (total - kept.len()) as u64;
+                kept
+            }
+            None => detections,
+        };
+
         for det in detections {
             if det.score >= self.config.track_thresh {
                 remain_detections.push(det);
@@ -70,15 +291,23 @@ impl BYTETracker {
             }
         }
 
+        self.stats.high_detections += remain_detections.len() as u64;
+        self.stats.low_detections += detections_low.len() as u64;
+        stage_event!(
+            high = remain_detections.len(),
+            low = detections_low.len(),
+            "split detections"
+        );
+
         let detections = remain_detections
             .into_iter()
-            .map(|d| STrack::new(d.bbox, d.score))
+            .map(STrack::from_detection)
             .collect::<Vec<_>>();
 
         // Create track pool
         let mut unconfirmed = Vec::new();
         let mut tracked_stracks = Vec::new();
-        for track in self.tracked_stracks.drain(..) {
+        for track in self.tracked_stracks.iter().cloned() {
             if !track.is_activated {
                 unconfirmed.push(track);
             } else {
@@ -89,32 +318,50 @@ impl BYTETracker {
         let mut strack_pool = joint_stracks(tracked_stracks, &self.lost_stracks);
 
         // Step 2: First association, with high score detections
-        STrack::multi_predict(&mut strack_pool, &self.kalman_filter);
-
-        let pool_rects: Vec<Rect> = strack_pool.iter().map(|t| t.rect()).collect();
-        let det_rects: Vec<Rect> = detections.iter().map(|t| t.rect()).collect();
-        let mut dists = matching::iou_distance(&pool_rects, &det_rects);
-
-        let det_wrappers: Vec<Detection> = detections
-            .iter()
-            .map(|t| Detection::from_rect(t.rect(), t.score))
-            .collect();
-        matching::fuse_score(&mut dists, &det_wrappers);
+        {
+            stage_span!("bytetrack.prediction", pool_size = strack_pool.len());
+            timed(&mut self.stats.prediction_time, || {
+                STrack::multi_predict(&mut strack_pool, &self.kalman_filter);
+                if let Some(ground) = &self.ground_filter {
+                    strack_pool
+                        .iter_mut()
+                        .for_each(|t| t.ground_predict(ground));
+                }
+            });
+        }
 
         let AssignmentResult {
             matches,
             unmatched_tracks,
             unmatched_detections,
-        } = matching::linear_assignment(&dists, self.config.match_thresh);
+        } = {
+            stage_span!(
+                "bytetrack.first_association",
+                tracks = strack_pool.len(),
+                detections = detections.len()
+            );
+            let result = self.associate(
+                &strack_pool,
+                &detections,
+                true,
+                true,
+                self.config.match_thresh,
+            )?;
+            stage_event!(matches = result.matches.len(), "first association");
+            result
+        };
+        self.stats.first_matches += matches.len() as u64;
 
         for (itracked, idet) in matches {
             let mut track = strack_pool[itracked].clone();
             let det = &detections[idet];
             if track.state == TrackState::Tracked {
                 track.update(det, &self.kalman_filter, self.frame_id);
+                self.observe(&mut track, det);
                 activated_stracks.push(track);
             } else {
                 track.re_activate(det, &self.kalman_filter, self.frame_id, false);
+                self.observe(&mut track, det);
                 refind_stracks.push(track);
             }
         }
@@ -122,7 +369,7 @@ impl BYTETracker {
         // Step 3: Second association, with low score detection boxes
         let detections_second = detections_low
             .into_iter()
-            .map(|d| STrack::new(d.bbox, d.score))
+            .map(STrack::from_detection)
             .collect::<Vec<_>>();
 
         let mut r_tracked_stracks = Vec::new();
@@ -132,24 +379,33 @@ impl BYTETracker {
             }
         }
 
-        let r_rects: Vec<Rect> = r_tracked_stracks.iter().map(|t| t.rect()).collect();
-        let det_low_rects: Vec<Rect> = detections_second.iter().map(|t| t.rect()).collect();
-        let dists_second = matching::iou_distance(&r_rects, &det_low_rects);
-
         let AssignmentResult {
             matches: matches_second,
             unmatched_tracks: unmatched_tracks_second,
             ..
-        } = matching::linear_assignment(&dists_second, 0.5);
+        } = {
+            stage_span!(
+                "bytetrack.second_association",
+                tracks = r_tracked_stracks.len(),
+                detections = detections_second.len()
+            );
+            let result =
+                self.associate(&r_tracked_stracks, &detections_second, false, false, 0.5)?;
+            stage_event!(matches = result.matches.len(), "second association");
+            result
+        };
+        self.stats.second_matches += matches_second.len() as u64;
 
         for (itracked, idet) in matches_second {
             let mut track = r_tracked_stracks[itracked].clone();
             let det = &detections_second[idet];
             if track.state == TrackState::Tracked {
                 track.update(det, &self.kalman_filter, self.frame_id);
+                self.observe(&mut track, det);
                 activated_stracks.push(track);
             } else {
                 track.re_activate(det, &self.kalman_filter, self.frame_id, false);
+                self.observe(&mut track, det);
                 refind_stracks.push(track);
             }
         }
@@ -159,6 +415,7 @@ impl BYTETracker {
             if track.state != TrackState::Lost {
                 track.mark_lost();
                 lost_stracks.push(track);
+                self.stats.tracks_lost += 1;
             }
         }
 
@@ -168,24 +425,25 @@ impl BYTETracker {
             detections_rem.push(detections[idx].clone());
         }
 
-        let unconfirmed_rects: Vec<Rect> = unconfirmed.iter().map(|t| t.rect()).collect();
-        let det_rem_rects: Vec<Rect> = detections_rem.iter().map(|t| t.rect()).collect();
-        let mut dist_unconfirmed = matching::iou_distance(&unconfirmed_rects, &det_rem_rects);
-
-        let det_rem_wrappers: Vec<Detection> = detections_rem
-            .iter()
-            .map(|t| Detection::from_rect(t.rect(), t.score))
-            .collect();
-        matching::fuse_score(&mut dist_unconfirmed, &det_rem_wrappers);
-
         let AssignmentResult {
             matches: matches_unconfirmed,
             unmatched_tracks: unmatched_unconfirmed,
             unmatched_detections: unmatched_new,
-        } = matching::linear_assignment(&dist_unconfirmed, 0.7);
+        } = {
+            stage_span!(
+                "bytetrack.unconfirmed_association",
+                tracks = unconfirmed.len(),
+                detections = detections_rem.len()
+            );
+            let result = self.associate(&unconfirmed, &detections_rem, true, false, 0.7)?;
+            stage_event!(matches = result.matches.len(), "unconfirmed association");
+            result
+        };
+        self.stats.unconfirmed_matches += matches_unconfirmed.len() as u64;
 
         for (itracked, idet) in matches_unconfirmed {
             unconfirmed[itracked].update(&detections_rem[idet], &self.kalman_filter, self.frame_id);
+            self.observe(&mut unconfirmed[itracked], &detections_rem[idet]);
             activated_stracks.push(unconfirmed[itracked].clone());
         }
         for idx in unmatched_unconfirmed {
@@ -200,13 +458,33 @@ impl BYTETracker {
             if track.score < self.config.track_thresh + 0.1 {
                 continue;
             }
+            if let Some(region) = &self.config.region
+                && !region.allows_birth(&track.tlwh.bounding_rect())
+            {
+                continue;
+            }
+            if let Some(capacity) = self.config.history_length {
+                track = track.with_history(capacity);
+            }
             track.activate(&self.kalman_filter, self.frame_id);
+            let det = track.clone();
+            self.observe(&mut track, &det);
             activated_stracks.push(track);
+            self.stats.tracks_started += 1;
         }
 
         // Step 5: Update state
         for mut track in self.lost_stracks.drain(..) {
-            if self.frame_id - track.end_frame() > self.max_time_lost {
+            let time_lost = self.frame_id - track.end_frame();
+            let max_time_lost = match frame_size {
+                Some((width, height))
+                    if !track.predicted_in_frame(self.frame_id, width, height) =>
+                {
+                    self.max_time_out_of_frame
+                }
+                _ => self.max_time_lost,
+            };
+            if time_lost > max_time_lost {
                 track.mark_removed();
                 removed_stracks.push(track);
             } else {
@@ -216,26 +494,222 @@ impl BYTETracker {
 
         self.tracked_stracks = activated_stracks
             .into_iter()
-            .chain(refind_stracks.into_iter())
+            .chain(refind_stracks)
             .filter(|t| t.state == TrackState::Tracked)
             .collect();
 
         self.lost_stracks = sub_stracks(lost_stracks, &self.tracked_stracks);
+
+        if let Some(region) = self.config.region.as_ref().filter(|r| r.remove_on_exit) {
+            for list in [&mut self.tracked_stracks, &mut self.lost_stracks] {
+                let (kept, exited): (Vec<_>, Vec<_>) =
+                    list.drain(..).partition(|t| region.in_roi(&t.rect()));
+                *list = kept;
+                removed_stracks.extend(exited.into_iter().map(|mut t| {
+                    t.mark_removed();
+                    t
+                }));
+            }
+        }
+        self.stats.tracks_removed += removed_stracks.len() as u64;
         self.removed_stracks.extend(removed_stracks);
 
-        let (tracked, lost) = remove_duplicate_stracks(&self.tracked_stracks, &self.lost_stracks);
-        self.tracked_stracks = tracked;
-        self.lost_stracks = lost;
+        {
+            stage_span!(
+                "bytetrack.duplicate_removal",
+                tracked = self.tracked_stracks.len(),
+                lost = self.lost_stracks.len()
+            );
+            let (tracked, lost) = timed(&mut self.stats.duplicate_removal_time, || {
+                remove_duplicate_stracks(&self.tracked_stracks, &self.lost_stracks)
+            });
+            self.tracked_stracks = tracked;
+            self.lost_stracks = lost;
+        }
 
-        self.tracked_stracks
+        self.stats.frames += 1;
+        self.stats.tracked = self.tracked_stracks.len();
+        self.stats.lost = self.lost_stracks.len();
+        self.stats.removed = self.removed_stracks.len();
+        self.stats.total_time += start.elapsed();
+
+        Ok(self
+            .tracked_stracks
             .iter()
             .filter(|t| t.is_activated)
-            .cloned()
-            .collect()
+            .map(|t| STrack {
+                frame_size,
+                ..t.clone()
+            })
+            .collect())
+    }
+
+    /// Update a track's ground-plane filter and smoothed keypoints from its
+    /// matched detection.
+    fn observe(&self, track: &mut STrack<B>, det: &STrack<B>) {
+        if let (Some(filter), Some(ground)) = (&self.ground_filter, &self.config.ground_plane)
+            && let Some(point) = ground.calibration.foot_point(&det.tlwh.bounding_rect())
+        {
+            track.ground_update(filter, point);
+        }
+        if let (Some(config), Some(current)) = (&self.config.keypoints, &det.keypoints) {
+            match &mut track.smoothed_keypoints {
+                Some(smoothed) => {
+                    smooth_keypoints(smoothed, current, config.smoothing, config.min_confidence)
+                }
+                None => track.smoothed_keypoints = Some(current.clone()),
+            }
+        }
+    }
+
+    /// Match tracks to detections by IoU distance, optionally fused with
+    /// detection scores and keypoint similarity, using the sparse path when
+    /// enabled.
+    fn associate(
+        &mut self,
+        tracks: &[STrack<B>],
+        detections: &[STrack<B>],
+        fuse: bool,
+        use_keypoints: bool,
+        thresh: f32,
+    ) -> Result<AssignmentResult, AssignmentError> {
+        let affinity = self.config.affinity;
+        let track_rects: Vec<B> = tracks.iter().map(|t| t.tlwh()).collect();
+        let det_rects: Vec<B> = detections.iter().map(|t| t.tlwh()).collect();
+        let det_wrappers = || -> Vec<Detection<B>> {
+            detections
+                .iter()
+                .map(|t| Detection::from_rect(t.tlwh(), t.score))
+                .collect()
+        };
+
+        // Blend in mask IoU for overlapping pairs that both carry masks.
+        let mask_weight = self.config.mask_weight;
+        let use_masks = mask_weight > 0.0
+            && tracks.iter().any(|t| t.mask.is_some())
+            && detections.iter().any(|d| d.mask.is_some());
+
+        // Blend in OKS against each track's keypoints, moved along by its
+        // velocity since they were observed.
+        let keypoint_config = self.config.keypoints.as_ref().filter(|_| use_keypoints);
+        let track_keypoints: Vec<Option<Vec<Keypoint>>> = match keypoint_config {
+            Some(_) if detections.iter().any(|d| d.keypoints.is_some()) => tracks
+                .iter()
+                .map(|t| {
+                    let keypoints = t.smoothed_keypoints.as_ref().or(t.keypoints.as_ref())?;
+                    let steps = self.frame_id.saturating_sub(t.frame_id) as f32;
+                    let (vx, vy) = t.velocity().map_or((0.0, 0.0), |v| (v.vx, v.vy));
+                    Some(
+                        keypoints
+                            .iter()
+                            .map(|k| {
+                                Keypoint::new(k.x + vx * steps, k.y + vy * steps, k.confidence)
+                            })
+                            .collect(),
+                    )
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let use_oks = track_keypoints.iter().any(Option::is_some);
+
+        let refine = |i: usize, j: usize, mut cost: f32| -> f32 {
+            if cost >= 1.0 {
+                return cost;
+            }
+            if use_masks && let (Some(a), Some(b)) = (&tracks[i].mask, &detections[j].mask) {
+                cost = 1.0 - ((1.0 - mask_weight) * (1.0 - cost) + mask_weight * a.iou(b));
+            }
+            if let (Some(config), Some(Some(a)), Some(b)) = (
+                keypoint_config,
+                track_keypoints.get(i),
+                &detections[j].keypoints,
+            ) {
+                let area = detections[j].tlwh.bounding_rect().area();
+                if let Some(similarity) = oks(a, b, area, &config.sigmas, config.min_confidence) {
+                    let w = config.oks_weight;
+                    cost = 1.0 - ((1.0 - w) * (1.0 - cost) + w * similarity);
+                }
+            }
+            cost
+        };
+
+        // Pairs too far apart on the ground get the same cost as pairs that
+        // don't overlap, so they can never be matched.
+        let det_ground: Vec<Option<(f64, f64)>> = match &self.config.ground_plane {
+            Some(ground) if ground.max_distance.is_some() => detections
+                .iter()
+                .map(|d| ground.calibration.foot_point(&d.tlwh.bounding_rect()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let max_distance = self
+            .config
+            .ground_plane
+            .as_ref()
+            .and_then(|g| g.max_distance)
+            .unwrap_or(f64::INFINITY);
+        let gated = |i: usize, j: usize| -> bool {
+            match (
+                tracks[i].ground_position(),
+                det_ground.get(j).copied().flatten(),
+            ) {
+                (Some(t), Some(d)) => (t.0 - d.0).hypot(t.1 - d.1) > max_distance,
+                _ => false,
+            }
+        };
+
+        if self.config.sparse_association {
+            let dists = timed(&mut self.stats.iou_time, || {
+                let mut dists =
+                    sparse::sparse_affinity_distance(&track_rects, &det_rects, affinity);
+                if use_masks || use_oks {
+                    for (i, j, cost) in &mut dists.entries {
+                        *cost = refine(*i, *j, *cost);
+                    }
+                }
+                if fuse {
+                    dists.fuse_score(&det_wrappers());
+                }
+                if !det_ground.is_empty() {
+                    dists.entries.retain(|&(i, j, _)| !gated(i, j));
+                }
+                dists
+            });
+            timed(&mut self.stats.assignment_time, || {
+                sparse::linear_assignment_sparse(&dists, thresh, &self.config.assignment)
+            })
+        } else {
+            let dists = timed(&mut self.stats.iou_time, || {
+                let mut dists = matching::affinity_distance(&track_rects, &det_rects, affinity);
+                if use_masks || use_oks {
+                    for ((i, j), cost) in dists.indexed_iter_mut() {
+                        *cost = refine(i, j, *cost);
+                    }
+                }
+                if fuse {
+                    matching::fuse_score(&mut dists, &det_wrappers());
+                }
+                if !det_ground.is_empty() {
+                    for ((i, j), cost) in dists.indexed_iter_mut() {
+                        if gated(i, j) {
+                            *cost = 1.0;
+                        }
+                    }
+                }
+                dists
+            });
+            timed(&mut self.stats.assignment_time, || {
+                matching::linear_assignment(&dists, thresh, &self.config.assignment)
+            })
+        }
     }
 }
 
-pub fn joint_stracks(tlista: Vec<STrack>, tlistb: &[STrack]) -> Vec<STrack> {
+pub fn joint_stracks<B: BoxGeometry>(
+    tlista: Vec<STrack<B>>,
+    tlistb: &[STrack<B>],
+) -> Vec<STrack<B>> {
     let mut exists = std::collections::HashSet::new();
     let mut res = Vec::new();
     for t in tlista {
@@ -251,7 +725,7 @@ pub fn joint_stracks(tlista: Vec<STrack>, tlistb: &[STrack]) -> Vec<STrack> {
     res
 }
 
-pub fn sub_stracks(tlista: Vec<STrack>, tlistb: &[STrack]) -> Vec<STrack> {
+pub fn sub_stracks<B: BoxGeometry>(tlista: Vec<STrack<B>>, tlistb: &[STrack<B>]) -> Vec<STrack<B>> {
     let mut b_ids = std::collections::HashSet::new();
     for t in tlistb {
         b_ids.insert(t.track_id);
@@ -262,16 +736,16 @@ pub fn sub_stracks(tlista: Vec<STrack>, tlistb: &[STrack]) -> Vec<STrack> {
         .collect()
 }
 
-pub fn remove_duplicate_stracks(
-    stracksa: &[STrack],
-    stracksb: &[STrack],
-) -> (Vec<STrack>, Vec<STrack>) {
+pub fn remove_duplicate_stracks<B: BoxGeometry>(
+    stracksa: &[STrack<B>],
+    stracksb: &[STrack<B>],
+) -> (Vec<STrack<B>>, Vec<STrack<B>>) {
     if stracksa.is_empty() || stracksb.is_empty() {
         return (stracksa.to_vec(), stracksb.to_vec());
     }
 
-    let a_rects: Vec<Rect> = stracksa.iter().map(|t| t.rect()).collect();
-    let b_rects: Vec<Rect> = stracksb.iter().map(|t| t.rect()).collect();
+    let a_rects: Vec<B> = stracksa.iter().map(|t| t.tlwh()).collect();
+    let b_rects: Vec<B> = stracksb.iter().map(|t| t.tlwh()).collect();
     let ious = iou_batch(&a_rects, &b_rects);
 
     let mut dupa = vec![false; stracksa.len()];
@@ -0,0 +1,422 @@
+//! Cross-camera association of local tracks into global identities.
+//!
+//! Each camera runs its own `BYTETracker`, so an object walking from one
+//! camera to another gets unrelated local track IDs. `CrossCameraAssociator`
+//! links local `(camera, track_id)` pairs into global IDs using appearance
+//! embeddings, restricted by a `CameraTopology` of allowed transitions and
+//! travel-time windows.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use ndarray::Array2;
+
+use crate::tracker::assignment::{AssignmentError, AssignmentMethod};
+use crate::tracker::geometry::BoxGeometry;
+use crate::tracker::matching;
+use crate::tracker::strack::STrack;
+
+/// Allowed time between leaving one camera and appearing in another.
+///
+/// `min_seconds` may be negative for cameras with overlapping views.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TravelWindow {
+    pub min_seconds: f64,
+    pub max_seconds: f64,
+}
+
+impl TravelWindow {
+    pub fn new(min_seconds: f64, max_seconds: f64) -> Self {
+        Self {
+            min_seconds,
+            max_seconds,
+        }
+    }
+
+    /// Whether a gap of `seconds` falls inside the window.
+    pub fn contains(&self, seconds: f64) -> bool {
+        seconds >= self.min_seconds && seconds <= self.max_seconds
+    }
+}
+
+/// Allowed camera transitions and their travel-time windows.
+#[derive(Debug, Clone)]
+pub struct CameraTopology<C: Eq + Hash> {
+    transitions: HashMap<(C, C), TravelWindow>,
+}
+
+impl<C: Eq + Hash + Clone> CameraTopology<C> {
+    /// Create a topology with no allowed transitions.
+    pub fn new() -> Self {
+        Self {
+            transitions: HashMap::new(),
+        }
+    }
+
+    /// Allow objects to move from camera `from` to camera `to`.
+    ///
+    /// Use `from == to` to allow re-entry into the same camera.
+    pub fn with_transition(mut self, from: C, to: C, window: TravelWindow) -> Self {
+        self.transitions.insert((from, to), window);
+        self
+    }
+
+    /// Allow transitions in both directions with the same window.
+    pub fn with_bidirectional(self, a: C, b: C, window: TravelWindow) -> Self {
+        self.with_transition(a.clone(), b.clone(), window)
+            .with_transition(b, a, window)
+    }
+
+    /// Travel-time window from `from` to `to`, if the transition is allowed.
+    pub fn window(&self, from: &C, to: &C) -> Option<&TravelWindow> {
+        self.transitions.get(&(from.clone(), to.clone()))
+    }
+}
+
+impl<C: Eq + Hash + Clone> Default for CameraTopology<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A local track observed by one camera, with its appearance embedding.
+#[derive(Debug, Clone)]
+pub struct Tracklet<C> {
+    /// Camera the track belongs to.
+    pub camera: C,
+    /// Local track ID from that camera's tracker.
+    pub track_id: u64,
+    /// Time the track started, in seconds.
+    pub start_time: f64,
+    /// Time the track was last observed, in seconds.
+    pub end_time: f64,
+    /// Appearance embedding, e.g. from a re-identification model.
+    pub embedding: Vec<f32>,
+}
+
+impl<C> Tracklet<C> {
+    /// Build a tracklet from a track, converting frame numbers to seconds.
+    pub fn from_strack<B: BoxGeometry>(
+        camera: C,
+        track: &STrack<B>,
+        frame_rate: f32,
+        embedding: Vec<f32>,
+    ) -> Self {
+        let frame_rate = frame_rate as f64;
+        Self {
+            camera,
+            track_id: track.track_id,
+            start_time: track.start_frame as f64 / frame_rate,
+            end_time: track.frame_id as f64 / frame_rate,
+            embedding,
+        }
+    }
+}
+
+/// Configuration for `CrossCameraAssociator`.
+#[derive(Debug, Clone)]
+pub struct CrossCameraConfig {
+    /// Maximum cosine distance between embeddings to link two tracks.
+    pub max_embedding_distance: f32,
+    /// Weight of the previous embedding when updating an identity.
+    pub embedding_momentum: f32,
+    /// Solver used to link new tracks to identities.
+    pub assignment: AssignmentMethod,
+}
+
+impl Default for CrossCameraConfig {
+    fn default() -> Self {
+        Self {
+            max_embedding_distance: 0.3,
+            embedding_momentum: 0.9,
+            assignment: AssignmentMethod::default(),
+        }
+    }
+}
+
+/// A global identity and where it was last seen.
+#[derive(Debug, Clone)]
+struct Identity<C> {
+    embedding: Vec<f32>,
+    camera: C,
+    last_seen: f64,
+}
+
+/// Links local tracks from several cameras into global identities.
+pub struct CrossCameraAssociator<C: Eq + Hash + Clone> {
+    config: CrossCameraConfig,
+    topology: CameraTopology<C>,
+    identities: HashMap<u64, Identity<C>>,
+    local_to_global: HashMap<(C, u64), u64>,
+    next_id: u64,
+}
+
+impl<C: Eq + Hash + Clone> CrossCameraAssociator<C> {
+    /// Create a new associator for the given camera topology.
+    pub fn new(topology: CameraTopology<C>, config: CrossCameraConfig) -> Self {
+        Self {
+            config,
+            topology,
+            identities: HashMap::new(),
+            local_to_global: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Update with the current tracklets of all cameras.
+    ///
+    /// Tracklets whose `(camera, track_id)` was seen before keep their global
+    /// ID and refresh its embedding. New tracklets are matched to existing
+    /// identities whose last camera may transition to theirs within the
+    /// travel-time window and whose embedding is close enough; the rest get
+    /// new global IDs.
+    ///
+    /// # Returns
+    /// The global ID of each tracklet, in the same order as `tracklets`.
+    pub fn update(&mut self, tracklets: &[Tracklet<C>]) -> Result<Vec<u64>, AssignmentError> {
+        let mut new_indices = Vec::new();
+        let mut new_keys = HashSet::new();
+        for (index, tracklet) in tracklets.iter().enumerate() {
+            let key = (tracklet.camera.clone(), tracklet.track_id);
+            if !self.local_to_global.contains_key(&key) && new_keys.insert(key) {
+                new_indices.push(index);
+            }
+        }
+
+        // Whether an identity still visible elsewhere may be linked is left
+        // to the topology: overlapping cameras use a negative `min_seconds`.
+        let mut candidates: Vec<u64> = self.identities.keys().copied().collect();
+        candidates.sort_unstable();
+        let cost = Array2::from_shape_fn((new_indices.len(), candidates.len()), |(i, j)| {
+            self.link_cost(&tracklets[new_indices[i]], &self.identities[&candidates[j]])
+        });
+        let result = matching::linear_assignment(
+            &cost,
+            self.config.max_embedding_distance,
+            &self.config.assignment,
+        )?;
+
+        for (i, j) in result.matches {
+            let tracklet = &tracklets[new_indices[i]];
+            self.local_to_global
+                .insert((tracklet.camera.clone(), tracklet.track_id), candidates[j]);
+        }
+        for i in result.unmatched_tracks {
+            let tracklet = &tracklets[new_indices[i]];
+            let id = self.next_id;
+            self.next_id += 1;
+            self.identities.insert(
+                id,
+                Identity {
+                    embedding: normalized(&tracklet.embedding),
+                    camera: tracklet.camera.clone(),
+                    last_seen: tracklet.end_time,
+                },
+            );
+            self.local_to_global
+                .insert((tracklet.camera.clone(), tracklet.track_id), id);
+        }
+
+        // Refresh every identity with its tracklets, new or known.
+        let momentum = self.config.embedding_momentum;
+        Ok(tracklets
+            .iter()
+            .map(|tracklet| {
+                let id = self.local_to_global[&(tracklet.camera.clone(), tracklet.track_id)];
+                let identity = self
+                    .identities
+                    .get_mut(&id)
+                    .expect("mapped identity exists");
+                if tracklet.end_time >= identity.last_seen {
+                    identity.camera = tracklet.camera.clone();
+                    identity.last_seen = tracklet.end_time;
+                }
+                let embedding = normalized(&tracklet.embedding);
+                if embedding.len() == identity.embedding.len() {
+                    for (old, new) in identity.embedding.iter_mut().zip(&embedding) {
+                        *old = momentum * *old + (1.0 - momentum) * new;
+                    }
+                    identity.embedding = normalized(&identity.embedding);
+                }
+                id
+            })
+            .collect())
+    }
+
+    /// Cost of linking a new tracklet to an existing identity.
+    ///
+    /// Pairs the topology rules out cost just over `max_embedding_distance`,
+    /// so the assignment rejects them without stretching the cost range.
+    fn link_cost(&self, tracklet: &Tracklet<C>, identity: &Identity<C>) -> f32 {
+        let gap = tracklet.start_time - identity.last_seen;
+        let allowed = self
+            .topology
+            .window(&identity.camera, &tracklet.camera)
+            .is_some_and(|window| window.contains(gap));
+        if !allowed || tracklet.embedding.len() != identity.embedding.len() {
+            return self.config.max_embedding_distance + 1.0;
+        }
+        cosine_distance(&normalized(&tracklet.embedding), &identity.embedding)
+    }
+
+    /// Global ID assigned to a local track, if it has been seen.
+    pub fn global_id(&self, camera: &C, track_id: u64) -> Option<u64> {
+        self.local_to_global
+            .get(&(camera.clone(), track_id))
+            .copied()
+    }
+
+    /// Number of global identities.
+    pub fn identity_count(&self) -> usize {
+        self.identities.len()
+    }
+
+    /// Drop identities last seen before `time`, along with their local mappings.
+    pub fn forget_before(&mut self, time: f64) {
+        self.identities
+            .retain(|_, identity| identity.last_seen >= time);
+        let identities = &self.identities;
+        self.local_to_global
+            .retain(|_, id| identities.contains_key(id));
+    }
+
+    /// Get the camera topology.
+    pub fn topology(&self) -> &CameraTopology<C> {
+        &self.topology
+    }
+}
+
+fn normalized(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter().map(|x| x / norm).collect()
+    } else {
+        v.to_vec()
+    }
+}
+
+/// Cosine distance between two unit vectors, in `[0, 2]`.
+fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    (1.0 - dot).clamp(0.0, 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::assignment::AuctionSolver;
+
+    fn tracklet(
+        camera: &'static str,
+        track_id: u64,
+        start: f64,
+        end: f64,
+        emb: [f32; 3],
+    ) -> Tracklet<&'static str> {
+        Tracklet {
+            camera,
+            track_id,
+            start_time: start,
+            end_time: end,
+            embedding: emb.to_vec(),
+        }
+    }
+
+    fn associator() -> CrossCameraAssociator<&'static str> {
+        let topology = CameraTopology::new()
+            .with_transition("a", "b", TravelWindow::new(2.0, 10.0))
+            .with_transition("b", "a", TravelWindow::new(2.0, 10.0));
+        CrossCameraAssociator::new(topology, CrossCameraConfig::default())
+    }
+
+    #[test]
+    fn test_links_across_cameras() {
+        let mut assoc = associator();
+
+        let ids = assoc
+            .update(&[
+                tracklet("a", 1, 0.0, 5.0, [1.0, 0.0, 0.0]),
+                tracklet("a", 2, 0.0, 5.0, [0.0, 1.0, 0.0]),
+            ])
+            .unwrap();
+        assert_eq!(ids, vec![1, 2]);
+
+        // Track 7 in camera b looks like track 1 and arrives 4s later.
+        let ids = assoc
+            .update(&[
+                tracklet("b", 7, 9.0, 9.5, [0.95, 0.1, 0.0]),
+                tracklet("b", 8, 9.0, 9.5, [0.0, 0.0, 1.0]),
+            ])
+            .unwrap();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(assoc.global_id(&"b", 7), Some(1));
+
+        // Known local tracks keep their ID.
+        let ids = assoc
+            .update(&[tracklet("b", 7, 9.0, 10.0, [1.0, 0.0, 0.0])])
+            .unwrap();
+        assert_eq!(ids, vec![1]);
+    }
+
+    #[test]
+    fn test_respects_topology() {
+        let mut assoc = associator();
+        assoc
+            .update(&[tracklet("a", 1, 0.0, 5.0, [1.0, 0.0, 0.0])])
+            .unwrap();
+
+        // Too fast for the a -> b window, and c isn't connected at all.
+        let ids = assoc
+            .update(&[
+                tracklet("b", 1, 6.0, 6.0, [1.0, 0.0, 0.0]),
+                tracklet("c", 1, 8.0, 8.0, [1.0, 0.0, 0.0]),
+            ])
+            .unwrap();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(assoc.identity_count(), 3);
+
+        assoc.forget_before(6.0);
+        assert_eq!(assoc.identity_count(), 2);
+        assert_eq!(assoc.global_id(&"a", 1), None);
+    }
+
+    #[test]
+    fn test_auction_matches_lapjv() {
+        let at = |degrees: f32| {
+            let (sin, cos) = degrees.to_radians().sin_cos();
+            [cos, sin, 0.0]
+        };
+        let links = |assignment: AssignmentMethod| {
+            let topology =
+                CameraTopology::new().with_transition("a", "b", TravelWindow::new(2.0, 10.0));
+            let config = CrossCameraConfig {
+                assignment,
+                ..Default::default()
+            };
+            let mut assoc = CrossCameraAssociator::new(topology, config);
+            assoc
+                .update(&[
+                    tracklet("a", 1, 0.0, 5.0, at(0.0)),
+                    tracklet("a", 2, 0.0, 5.0, at(30.0)),
+                ])
+                .unwrap();
+            // Track 3 is closest to identity 1, but giving it identity 2
+            // lets track 4 take identity 1 at a lower total cost. Camera c
+            // isn't connected, so track 5 can't link to either.
+            assoc
+                .update(&[
+                    tracklet("b", 3, 9.0, 9.0, at(10.0)),
+                    tracklet("b", 4, 9.0, 9.0, at(-15.0)),
+                    tracklet("c", 5, 9.0, 9.0, at(10.0)),
+                ])
+                .unwrap()
+        };
+
+        let expected = links(AssignmentMethod::Lapjv);
+        assert_eq!(expected, vec![2, 1, 3]);
+        assert_eq!(
+            links(AssignmentMethod::Auction(AuctionSolver::default())),
+            expected
+        );
+    }
+}
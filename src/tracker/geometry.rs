@@ -0,0 +1,155 @@
+//! Box geometries the tracker can follow.
+
+use std::fmt::Debug;
+
+use ndarray::Array1;
+
+use crate::tracker::rect::Rect;
+
+/// Weight of the position noise relative to box height.
+const STD_WEIGHT_POSITION: f64 = 1.0 / 20.0;
+/// Weight of the velocity noise relative to box height.
+const STD_WEIGHT_VELOCITY: f64 = 1.0 / 160.0;
+
+/// How `BYTETracker` scores track/detection pairs for association.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Affinity {
+    /// Box overlap as given by `BoxGeometry::iou`.
+    #[default]
+    Iou,
+    /// Overlap of the boxes' footprints seen from above. Same as `Iou` for
+    /// image-space boxes.
+    BevIou,
+    /// Center distance, scaled linearly from `1` at zero distance to `0` at
+    /// `max_distance` and beyond.
+    CenterDistance { max_distance: f32 },
+}
+
+impl Affinity {
+    /// How far apart two boxes' bounding rects may be while still scoring
+    /// above zero.
+    pub(crate) fn search_margin(self) -> f32 {
+        match self {
+            Affinity::CenterDistance { max_distance } => max_distance.max(0.0),
+            _ => 0.0,
+        }
+    }
+}
+
+/// A box type that `BYTETracker` can associate and filter.
+///
+/// The Kalman state of a track holds the `DIM` measurement components
+/// followed by their per-frame rates. The first two components must be the
+/// box center, which motion accessors such as `STrack::velocity` rely on.
+pub trait BoxGeometry: Copy + Debug + Send + Sync + 'static {
+    /// Length of the measurement vector.
+    const DIM: usize;
+
+    /// Measurement vector of this box.
+    fn to_measurement(&self) -> Vec<f64>;
+
+    /// Box described by the first `DIM` components of a Kalman state.
+    fn from_state(state: &Array1<f64>) -> Self;
+
+    /// Overlap with another box, in `[0, 1]`.
+    fn iou(&self, other: &Self) -> f32;
+
+    /// Overlap of the footprints seen from above, in `[0, 1]`.
+    fn bev_iou(&self, other: &Self) -> f32 {
+        self.iou(other)
+    }
+
+    /// Distance between the box centers.
+    fn center_distance(&self, other: &Self) -> f32 {
+        let (ax, ay) = self.bounding_rect().center();
+        let (bx, by) = other.bounding_rect().center();
+        (ax - bx).hypot(ay - by)
+    }
+
+    /// Similarity with another box under `affinity`, in `[0, 1]`.
+    fn affinity(&self, other: &Self, affinity: Affinity) -> f32 {
+        match affinity {
+            Affinity::Iou => self.iou(other),
+            Affinity::BevIou => self.bev_iou(other),
+            Affinity::CenterDistance { max_distance } => {
+                if max_distance > 0.0 {
+                    (1.0 - self.center_distance(other) / max_distance).max(0.0)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Axis-aligned image-space box enclosing this one, used for spatial
+    /// indexing, regions, ground-plane foot points and frame clipping.
+    fn bounding_rect(&self) -> Rect;
+
+    /// Standard deviations of a new track's state (length `2 * DIM`).
+    fn initial_std(measurement: &[f64]) -> Vec<f64>;
+
+    /// Standard deviations of the per-frame process noise (length `2 * DIM`).
+    fn process_std(state: &Array1<f64>) -> Vec<f64>;
+
+    /// Standard deviations of the measurement noise (length `DIM`).
+    fn measurement_std(state: &Array1<f64>) -> Vec<f64>;
+
+    /// Bring measurement residuals of periodic components, such as angles,
+    /// into their principal range.
+    fn wrap_residual(_residual: &mut Array1<f64>) {}
+
+    /// Adjust the state of a track that is not currently tracked before
+    /// predicting it. ByteTrack stops the box size from changing.
+    fn hold_size(_state: &mut Array1<f64>) {}
+}
+
+/// Axis-aligned boxes, filtered in XYAH form (center, aspect ratio, height).
+impl BoxGeometry for Rect {
+    const DIM: usize = 4;
+
+    fn to_measurement(&self) -> Vec<f64> {
+        self.to_xyah().iter().map(|&v| v as f64).collect()
+    }
+
+    fn from_state(state: &Array1<f64>) -> Self {
+        Rect::from_xyah(
+            state[0] as f32,
+            state[1] as f32,
+            state[2] as f32,
+            state[3] as f32,
+        )
+    }
+
+    fn iou(&self, other: &Self) -> f32 {
+        Rect::iou(self, other)
+    }
+
+    fn bounding_rect(&self) -> Rect {
+        *self
+    }
+
+    fn initial_std(measurement: &[f64]) -> Vec<f64> {
+        let (pos, vel) = (
+            2.0 * STD_WEIGHT_POSITION * measurement[3],
+            10.0 * STD_WEIGHT_VELOCITY * measurement[3],
+        );
+        vec![pos, pos, 1e-2, pos, vel, vel, 1e-5, vel]
+    }
+
+    fn process_std(state: &Array1<f64>) -> Vec<f64> {
+        let (pos, vel) = (
+            STD_WEIGHT_POSITION * state[3],
+            STD_WEIGHT_VELOCITY * state[3],
+        );
+        vec![pos, pos, 1e-2, pos, vel, vel, 1e-5, vel]
+    }
+
+    fn measurement_std(state: &Array1<f64>) -> Vec<f64> {
+        let pos = STD_WEIGHT_POSITION * state[3];
+        vec![pos, pos, 1e-1, pos]
+    }
+
+    fn hold_size(state: &mut Array1<f64>) {
+        state[7] = 0.0;
+    }
+}
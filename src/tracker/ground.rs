@@ -0,0 +1,402 @@
+//! Ground-plane calibration and world-space motion model.
+//!
+//! A `GroundCalibration` maps image pixels to ground-plane coordinates
+//! (e.g. metres) with a homography. Tracks are located on the ground by their
+//! foot point, the bottom-center of their box. With a `GroundPlaneConfig` in
+//! `TrackerConfig`, each track also runs a constant-velocity Kalman filter on
+//! its world position, so velocities are in world units per second and
+//! matches can be gated by physical distance.
+
+use nalgebra::{DMatrix, Matrix3, SymmetricEigen, Vector3};
+use ndarray::{Array1, Array2};
+
+use crate::tracker::geometry::BoxGeometry;
+use crate::tracker::rect::Rect;
+use crate::tracker::strack::STrack;
+
+/// Error type for calibration failures.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrationError {
+    /// At least four point correspondences are needed.
+    TooFewPoints(usize),
+    /// The points don't determine an invertible homography, e.g. because
+    /// three of them are collinear.
+    Degenerate,
+}
+
+impl std::fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooFewPoints(n) => {
+                write!(f, "Need at least 4 point correspondences, got {}", n)
+            }
+            Self::Degenerate => write!(f, "Point correspondences are degenerate"),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+/// A 3x3 planar homography acting on homogeneous 2D points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Homography {
+    matrix: Matrix3<f64>,
+}
+
+impl Homography {
+    /// Create a homography from a row-major 3x3 matrix.
+    pub fn new(matrix: [[f64; 3]; 3]) -> Self {
+        Self {
+            matrix: Matrix3::from_fn(|i, j| matrix[i][j]),
+        }
+    }
+
+    /// Estimate the homography mapping each `src` point onto its `dst`
+    /// point, using the normalized direct linear transform.
+    pub fn from_correspondences(
+        src: &[(f64, f64)],
+        dst: &[(f64, f64)],
+    ) -> Result<Self, CalibrationError> {
+        let n = src.len().min(dst.len());
+        if n < 4 {
+            return Err(CalibrationError::TooFewPoints(n));
+        }
+
+        let t_src = normalizing_transform(&src[..n]);
+        let t_dst = normalizing_transform(&dst[..n]);
+
+        let mut a = DMatrix::<f64>::zeros(2 * n, 9);
+        for k in 0..n {
+            let p = t_src * Vector3::new(src[k].0, src[k].1, 1.0);
+            let q = t_dst * Vector3::new(dst[k].0, dst[k].1, 1.0);
+            let (x, y) = (p.x / p.z, p.y / p.z);
+            let (u, v) = (q.x / q.z, q.y / q.z);
+            let rows = [
+                [-x, -y, -1.0, 0.0, 0.0, 0.0, u * x, u * y, u],
+                [0.0, 0.0, 0.0, -x, -y, -1.0, v * x, v * y, v],
+            ];
+            for (r, row) in rows.iter().enumerate() {
+                for (c, value) in row.iter().enumerate() {
+                    a[(2 * k + r, c)] = *value;
+                }
+            }
+        }
+
+        // The solution is the eigenvector of A^T A with the smallest eigenvalue.
+        let eigen = SymmetricEigen::new(a.transpose() * &a);
+        let (min_idx, _) = eigen
+            .eigenvalues
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .ok_or(CalibrationError::Degenerate)?;
+        let h = eigen.eigenvectors.column(min_idx);
+        let normalized = Matrix3::from_fn(|i, j| h[3 * i + j]);
+
+        let t_dst_inv = t_dst.try_inverse().ok_or(CalibrationError::Degenerate)?;
+        let matrix = t_dst_inv * normalized * t_src;
+        if matrix[(2, 2)].abs() < 1e-12 || matrix.try_inverse().is_none() {
+            return Err(CalibrationError::Degenerate);
+        }
+        Ok(Self {
+            matrix: matrix / matrix[(2, 2)],
+        })
+    }
+
+    /// Apply the homography to a point. Returns `None` for points that map
+    /// to infinity, such as those on the horizon line.
+    pub fn apply(&self, point: (f64, f64)) -> Option<(f64, f64)> {
+        let p = self.matrix * Vector3::new(point.0, point.1, 1.0);
+        if p.z.abs() < 1e-12 {
+            return None;
+        }
+        Some((p.x / p.z, p.y / p.z))
+    }
+
+    /// The inverse homography, if the matrix is invertible.
+    pub fn inverse(&self) -> Option<Self> {
+        self.matrix.try_inverse().map(|matrix| Self { matrix })
+    }
+
+    /// The matrix in row-major order.
+    pub fn to_array(&self) -> [[f64; 3]; 3] {
+        std::array::from_fn(|i| std::array::from_fn(|j| self.matrix[(i, j)]))
+    }
+}
+
+/// Similarity transform moving points to zero mean and mean distance sqrt(2).
+fn normalizing_transform(points: &[(f64, f64)]) -> Matrix3<f64> {
+    let n = points.len() as f64;
+    let (cx, cy) = points
+        .iter()
+        .fold((0.0, 0.0), |(sx, sy), p| (sx + p.0 / n, sy + p.1 / n));
+    let mean_dist = points
+        .iter()
+        .map(|p| ((p.0 - cx).powi(2) + (p.1 - cy).powi(2)).sqrt())
+        .sum::<f64>()
+        / n;
+    let s = if mean_dist > 0.0 {
+        std::f64::consts::SQRT_2 / mean_dist
+    } else {
+        1.0
+    };
+    Matrix3::new(s, 0.0, -s * cx, 0.0, s, -s * cy, 0.0, 0.0, 1.0)
+}
+
+/// Image-to-ground calibration for one camera.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GroundCalibration {
+    image_to_ground: Homography,
+    ground_to_image: Homography,
+}
+
+impl GroundCalibration {
+    /// Create a calibration from an image-to-ground homography.
+    pub fn new(image_to_ground: Homography) -> Result<Self, CalibrationError> {
+        let ground_to_image = image_to_ground
+            .inverse()
+            .ok_or(CalibrationError::Degenerate)?;
+        Ok(Self {
+            image_to_ground,
+            ground_to_image,
+        })
+    }
+
+    /// Estimate a calibration from at least four image points and their
+    /// known ground-plane positions.
+    pub fn from_correspondences(
+        image_points: &[(f64, f64)],
+        ground_points: &[(f64, f64)],
+    ) -> Result<Self, CalibrationError> {
+        Self::new(Homography::from_correspondences(
+            image_points,
+            ground_points,
+        )?)
+    }
+
+    /// Project an image point onto the ground plane.
+    pub fn image_to_ground(&self, point: (f64, f64)) -> Option<(f64, f64)> {
+        self.image_to_ground.apply(point)
+    }
+
+    /// Project a ground-plane point into the image.
+    pub fn ground_to_image(&self, point: (f64, f64)) -> Option<(f64, f64)> {
+        self.ground_to_image.apply(point)
+    }
+
+    /// Ground position of a box's foot point, its bottom-center.
+    pub fn foot_point(&self, rect: &Rect) -> Option<(f64, f64)> {
+        let x = rect.x as f64 + rect.width as f64 / 2.0;
+        let y = rect.y as f64 + rect.height as f64;
+        self.image_to_ground((x, y))
+    }
+
+    /// Ground position of a track's foot point, from its current box.
+    pub fn track_position<B: BoxGeometry>(&self, track: &STrack<B>) -> Option<(f64, f64)> {
+        self.foot_point(&track.rect())
+    }
+
+    /// The image-to-ground homography.
+    pub fn homography(&self) -> &Homography {
+        &self.image_to_ground
+    }
+}
+
+/// Ground-plane tracking mode for `TrackerConfig`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroundPlaneConfig {
+    /// Camera calibration.
+    pub calibration: GroundCalibration,
+    /// Reject matches whose detection foot point is farther than this from
+    /// the track's predicted ground position, in world units.
+    pub max_distance: Option<f64>,
+    /// Standard deviation of foot point measurements, in world units.
+    pub measurement_std: f64,
+    /// Standard deviation of random acceleration, in world units per second squared.
+    pub acceleration_std: f64,
+}
+
+impl GroundPlaneConfig {
+    pub fn new(calibration: GroundCalibration) -> Self {
+        Self {
+            calibration,
+            max_distance: None,
+            measurement_std: 0.5,
+            acceleration_std: 2.0,
+        }
+    }
+
+    /// Gate matches by ground-plane distance.
+    pub fn with_max_distance(mut self, distance: f64) -> Self {
+        self.max_distance = Some(distance);
+        self
+    }
+}
+
+/// Constant-velocity Kalman filter on `[x, y, vx, vy]` ground coordinates.
+#[derive(Debug, Clone)]
+pub struct GroundKalmanFilter {
+    dt: f64,
+    measurement_std: f64,
+    acceleration_std: f64,
+}
+
+impl GroundKalmanFilter {
+    pub fn new(config: &GroundPlaneConfig, frame_rate: f32) -> Self {
+        Self {
+            dt: 1.0 / frame_rate.max(f32::EPSILON) as f64,
+            measurement_std: config.measurement_std,
+            acceleration_std: config.acceleration_std,
+        }
+    }
+
+    pub fn initiate(&self, measurement: (f64, f64)) -> (Array1<f64>, Array2<f64>) {
+        let mean = Array1::from_vec(vec![measurement.0, measurement.1, 0.0, 0.0]);
+        let pos_var = self.measurement_std.powi(2);
+        // Allow fast initial motion until a few measurements arrive.
+        let vel_var = (10.0 * self.measurement_std).powi(2);
+        let covariance =
+            Array2::from_diag(&Array1::from_vec(vec![pos_var, pos_var, vel_var, vel_var]));
+        (mean, covariance)
+    }
+
+    pub fn predict(
+        &self,
+        mean: &Array1<f64>,
+        covariance: &Array2<f64>,
+    ) -> (Array1<f64>, Array2<f64>) {
+        let dt = self.dt;
+        let mut motion = Array2::eye(4);
+        motion[[0, 2]] = dt;
+        motion[[1, 3]] = dt;
+
+        // Discrete white-noise acceleration model, independent per axis.
+        let q = self.acceleration_std.powi(2);
+        let mut noise = Array2::zeros((4, 4));
+        for axis in 0..2 {
+            noise[[axis, axis]] = q * dt.powi(4) / 4.0;
+            noise[[axis, axis + 2]] = q * dt.powi(3) / 2.0;
+            noise[[axis + 2, axis]] = q * dt.powi(3) / 2.0;
+            noise[[axis + 2, axis + 2]] = q * dt.powi(2);
+        }
+
+        let new_mean = motion.dot(mean);
+        let new_covariance = motion.dot(covariance).dot(&motion.t()) + noise;
+        (new_mean, new_covariance)
+    }
+
+    pub fn update(
+        &self,
+        mean: &Array1<f64>,
+        covariance: &Array2<f64>,
+        measurement: (f64, f64),
+    ) -> (Array1<f64>, Array2<f64>) {
+        let r = self.measurement_std.powi(2);
+        // H = [I 0], so S is the top-left 2x2 block of P plus R.
+        let s = [
+            [covariance[[0, 0]] + r, covariance[[0, 1]]],
+            [covariance[[1, 0]], covariance[[1, 1]] + r],
+        ];
+        let det = s[0][0] * s[1][1] - s[0][1] * s[1][0];
+        if det.abs() < 1e-12 {
+            return (mean.clone(), covariance.clone());
+        }
+        let s_inv = [
+            [s[1][1] / det, -s[0][1] / det],
+            [-s[1][0] / det, s[0][0] / det],
+        ];
+
+        // K = P H^T S^-1 (4x2)
+        let mut gain = Array2::zeros((4, 2));
+        for i in 0..4 {
+            for j in 0..2 {
+                gain[[i, j]] = covariance[[i, 0]] * s_inv[0][j] + covariance[[i, 1]] * s_inv[1][j];
+            }
+        }
+
+        let innovation = Array1::from_vec(vec![measurement.0 - mean[0], measurement.1 - mean[1]]);
+        let new_mean = mean + &gain.dot(&innovation);
+        // P' = P - K H P
+        let hp = covariance.slice(ndarray::s![0..2, ..]).to_owned();
+        let new_covariance = covariance - &gain.dot(&hp);
+        (new_mean, new_covariance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: (f64, f64), b: (f64, f64)) {
+        assert!(
+            (a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn test_homography_from_correspondences() {
+        let truth = Homography::new([
+            [0.02, 0.001, -3.0],
+            [0.0005, 0.05, -10.0],
+            [0.0, 0.001, 1.0],
+        ]);
+        let image = [
+            (100.0, 400.0),
+            (500.0, 420.0),
+            (80.0, 700.0),
+            (600.0, 650.0),
+            (300.0, 500.0),
+        ];
+        let ground: Vec<(f64, f64)> = image.iter().map(|&p| truth.apply(p).unwrap()).collect();
+
+        let estimated = Homography::from_correspondences(&image, &ground).unwrap();
+        for &p in &image {
+            assert_close(estimated.apply(p).unwrap(), truth.apply(p).unwrap());
+        }
+
+        assert_eq!(
+            Homography::from_correspondences(&image[..3], &ground[..3]),
+            Err(CalibrationError::TooFewPoints(3))
+        );
+    }
+
+    #[test]
+    fn test_foot_point_round_trip() {
+        // 10 pixels per metre, origin at pixel (0, 0).
+        let calibration = GroundCalibration::new(Homography::new([
+            [0.1, 0.0, 0.0],
+            [0.0, 0.1, 0.0],
+            [0.0, 0.0, 1.0],
+        ]))
+        .unwrap();
+
+        let foot = calibration
+            .foot_point(&Rect::new(10.0, 20.0, 20.0, 40.0))
+            .unwrap();
+        assert_close(foot, (2.0, 6.0));
+        assert_close(calibration.ground_to_image(foot).unwrap(), (20.0, 60.0));
+    }
+
+    #[test]
+    fn test_ground_filter_estimates_velocity() {
+        let calibration = GroundCalibration::new(Homography::new([
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ]))
+        .unwrap();
+        let filter = GroundKalmanFilter::new(&GroundPlaneConfig::new(calibration), 10.0);
+
+        // Moving at 2 units per second, sampled at 10 Hz.
+        let (mut mean, mut cov) = filter.initiate((0.0, 0.0));
+        for k in 1..50 {
+            (mean, cov) = filter.predict(&mean, &cov);
+            (mean, cov) = filter.update(&mean, &cov, (0.2 * k as f64, 0.0));
+        }
+        assert!((mean[2] - 2.0).abs() < 0.05, "vx = {}", mean[2]);
+        assert!(mean[3].abs() < 0.05);
+    }
+}
@@ -0,0 +1,118 @@
+//! Bounded per-track trajectory history.
+
+use std::collections::VecDeque;
+
+use crate::tracker::geometry::BoxGeometry;
+use crate::tracker::rect::Rect;
+
+/// One observation of a track.
+#[derive(Debug, Clone, Copy)]
+pub struct TrajectoryPoint<B: BoxGeometry = Rect> {
+    /// Frame the observation was made in.
+    pub frame_id: u32,
+    /// Detection box the track was matched to.
+    pub observed: B,
+    /// Kalman-filtered box after the update.
+    pub filtered: B,
+    /// Detection confidence score.
+    pub score: f32,
+}
+
+/// Ring buffer of the most recent observations of a track.
+#[derive(Debug, Clone)]
+pub struct TrajectoryHistory<B: BoxGeometry = Rect> {
+    capacity: usize,
+    points: VecDeque<TrajectoryPoint<B>>,
+}
+
+impl<B: BoxGeometry> TrajectoryHistory<B> {
+    /// Create an empty history keeping at most `capacity` points.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            points: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Append a point, dropping the oldest one when full.
+    pub fn push(&mut self, point: TrajectoryPoint<B>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.points.len() == self.capacity {
+            self.points.pop_front();
+        }
+        self.points.push_back(point);
+    }
+
+    /// Points from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &TrajectoryPoint<B>> + ExactSizeIterator {
+        self.points.iter()
+    }
+
+    /// Most recent point.
+    pub fn latest(&self) -> Option<&TrajectoryPoint<B>> {
+        self.points.back()
+    }
+
+    /// Maximum number of points kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Frames spanned by the stored points, from first to last observation.
+    pub fn span_frames(&self) -> u32 {
+        match (self.points.front(), self.points.back()) {
+            (Some(first), Some(last)) => last.frame_id - first.frame_id,
+            _ => 0,
+        }
+    }
+}
+
+impl<'a, B: BoxGeometry> IntoIterator for &'a TrajectoryHistory<B> {
+    type Item = &'a TrajectoryPoint<B>;
+    type IntoIter = std::collections::vec_deque::Iter<'a, TrajectoryPoint<B>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.points.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(frame_id: u32) -> TrajectoryPoint {
+        let rect = Rect::new(frame_id as f32, 0.0, 10.0, 10.0);
+        TrajectoryPoint {
+            frame_id,
+            observed: rect,
+            filtered: rect,
+            score: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = TrajectoryHistory::new(3);
+        for frame in 1..=5 {
+            history.push(point(frame));
+        }
+        let frames: Vec<u32> = history.iter().map(|p| p.frame_id).collect();
+        assert_eq!(frames, vec![3, 4, 5]);
+        assert_eq!(history.latest().unwrap().frame_id, 5);
+        assert_eq!(history.span_frames(), 2);
+
+        let mut empty = TrajectoryHistory::new(0);
+        empty.push(point(1));
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+//! Pose keypoints: Object Keypoint Similarity and per-track smoothing.
+
+/// Per-keypoint OKS constants for the 17 COCO person keypoints.
+pub const COCO_KEYPOINT_SIGMAS: [f32; 17] = [
+    0.026, 0.025, 0.025, 0.035, 0.035, 0.079, 0.079, 0.072, 0.072, 0.062, 0.062, 0.107, 0.107,
+    0.087, 0.087, 0.089, 0.089,
+];
+
+/// A single keypoint in image coordinates.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Keypoint {
+    pub x: f32,
+    pub y: f32,
+    /// Detector confidence for this keypoint.
+    pub confidence: f32,
+}
+
+impl Keypoint {
+    pub fn new(x: f32, y: f32, confidence: f32) -> Self {
+        Self { x, y, confidence }
+    }
+}
+
+/// Keypoint-aware association and smoothing settings.
+#[derive(Debug, Clone)]
+pub struct KeypointConfig {
+    /// Per-keypoint OKS constants; keypoints beyond this list are ignored.
+    pub sigmas: Vec<f32>,
+    /// Weight of OKS against box IoU in the first association.
+    pub oks_weight: f32,
+    /// Keypoints below this confidence are ignored by OKS and don't move
+    /// the smoothed keypoints.
+    pub min_confidence: f32,
+    /// Weight of each new observation in the smoothed keypoints, in
+    /// `(0, 1]`; `1.0` disables smoothing.
+    pub smoothing: f32,
+}
+
+impl Default for KeypointConfig {
+    fn default() -> Self {
+        Self {
+            sigmas: COCO_KEYPOINT_SIGMAS.to_vec(),
+            oks_weight: 0.5,
+            min_confidence: 0.3,
+            smoothing: 0.5,
+        }
+    }
+}
+
+impl KeypointConfig {
+    pub fn with_sigmas(mut self, sigmas: Vec<f32>) -> Self {
+        self.sigmas = sigmas;
+        self
+    }
+
+    pub fn with_oks_weight(mut self, weight: f32) -> Self {
+        self.oks_weight = weight;
+        self
+    }
+
+    pub fn with_min_confidence(mut self, confidence: f32) -> Self {
+        self.min_confidence = confidence;
+        self
+    }
+
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+}
+
+/// Object Keypoint Similarity between two keypoint sets of an object with
+/// the given area (in pixels squared).
+///
+/// # Returns
+/// Mean similarity over keypoints confident in both sets, or `None` if
+/// there are none.
+pub fn oks(
+    a: &[Keypoint],
+    b: &[Keypoint],
+    area: f32,
+    sigmas: &[f32],
+    min_confidence: f32,
+) -> Option<f32> {
+    let (mut sum, mut count) = (0.0, 0);
+    for ((p, q), sigma) in a.iter().zip(b).zip(sigmas) {
+        if p.confidence < min_confidence || q.confidence < min_confidence {
+            continue;
+        }
+        let d2 = (p.x - q.x).powi(2) + (p.y - q.y).powi(2);
+        let k2 = (2.0 * sigma).powi(2);
+        sum += (-d2 / (2.0 * area.max(1.0) * k2)).exp();
+        count += 1;
+    }
+    (count > 0).then(|| sum / count as f32)
+}
+
+/// Blend `current` into `smoothed` with weight `alpha`.
+///
+/// Confident keypoints move the smoothed position; unconfident ones keep it
+/// and only decay its confidence. A keypoint that was not confident before
+/// jumps straight to the new position.
+pub fn smooth_keypoints(
+    smoothed: &mut Vec<Keypoint>,
+    current: &[Keypoint],
+    alpha: f32,
+    min_confidence: f32,
+) {
+    if smoothed.len() != current.len() {
+        *smoothed = current.to_vec();
+        return;
+    }
+    for (s, c) in smoothed.iter_mut().zip(current) {
+        if c.confidence >= min_confidence {
+            if s.confidence >= min_confidence {
+                s.x += alpha * (c.x - s.x);
+                s.y += alpha * (c.y - s.y);
+            } else {
+                (s.x, s.y) = (c.x, c.y);
+            }
+            s.confidence = c.confidence;
+        } else {
+            s.confidence += alpha * (c.confidence - s.confidence);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oks() {
+        let a = vec![
+            Keypoint::new(10.0, 10.0, 0.9),
+            Keypoint::new(20.0, 20.0, 0.9),
+        ];
+        let sigmas = [0.1, 0.1];
+        assert_eq!(oks(&a, &a, 100.0, &sigmas, 0.3), Some(1.0));
+
+        // One keypoint off by d: exp(-d^2 / (2 * area * (2 sigma)^2)).
+        let b = vec![
+            Keypoint::new(13.0, 14.0, 0.9),
+            Keypoint::new(20.0, 20.0, 0.1),
+        ];
+        let expected = (-25.0f32 / (2.0 * 100.0 * 0.04)).exp();
+        assert!((oks(&a, &b, 100.0, &sigmas, 0.3).unwrap() - expected).abs() < 1e-6);
+
+        let hidden = vec![Keypoint::new(0.0, 0.0, 0.0); 2];
+        assert_eq!(oks(&a, &hidden, 100.0, &sigmas, 0.3), None);
+    }
+
+    #[test]
+    fn test_smoothing() {
+        let mut smoothed = vec![Keypoint::new(0.0, 0.0, 0.9), Keypoint::new(0.0, 0.0, 0.1)];
+        let current = [Keypoint::new(10.0, 20.0, 0.8), Keypoint::new(5.0, 5.0, 0.9)];
+        smooth_keypoints(&mut smoothed, &current, 0.5, 0.3);
+        assert_eq!(smoothed[0], Keypoint::new(5.0, 10.0, 0.8));
+        assert_eq!(smoothed[1], Keypoint::new(5.0, 5.0, 0.9));
+
+        // An occluded keypoint holds its position and fades.
+        smooth_keypoints(
+            &mut smoothed,
+            &[current[0], Keypoint::new(50.0, 50.0, 0.1)],
+            0.5,
+            0.3,
+        );
+        assert_eq!((smoothed[1].x, smoothed[1].y), (5.0, 5.0));
+        assert!((smoothed[1].confidence - 0.5).abs() < 1e-6);
+    }
+}
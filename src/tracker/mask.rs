@@ -0,0 +1,315 @@
+//! Run-length encoded segmentation masks in the COCO format.
+
+use std::fmt;
+
+use crate::tracker::rect::Rect;
+
+/// Error creating an `RleMask`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MaskError {
+    /// The run lengths don't add up to `height * width`.
+    CountMismatch { expected: u64, actual: u64 },
+    /// The bitmap length doesn't match `height * width`.
+    BitmapSize { expected: usize, actual: usize },
+    /// A compressed COCO counts string is malformed.
+    InvalidString,
+}
+
+impl fmt::Display for MaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MaskError::CountMismatch { expected, actual } => {
+                write!(f, "RLE counts sum to {}, expected {}", actual, expected)
+            }
+            MaskError::BitmapSize { expected, actual } => {
+                write!(f, "bitmap has {} pixels, expected {}", actual, expected)
+            }
+            MaskError::InvalidString => write!(f, "invalid COCO RLE counts string"),
+        }
+    }
+}
+
+impl std::error::Error for MaskError {}
+
+/// Binary mask stored as COCO run lengths.
+///
+/// Pixels are visited in column-major order; runs alternate between
+/// background and foreground, starting with background (possibly empty).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RleMask {
+    height: u32,
+    width: u32,
+    counts: Vec<u32>,
+}
+
+impl RleMask {
+    /// Create a mask from uncompressed COCO run lengths.
+    pub fn new(height: u32, width: u32, counts: Vec<u32>) -> Result<Self, MaskError> {
+        let expected = height as u64 * width as u64;
+        let actual = counts.iter().map(|&c| c as u64).sum();
+        if actual != expected {
+            return Err(MaskError::CountMismatch { expected, actual });
+        }
+        Ok(Self {
+            height,
+            width,
+            counts,
+        })
+    }
+
+    /// Encode a row-major bitmap (`true` for foreground).
+    pub fn from_bitmap(height: u32, width: u32, bitmap: &[bool]) -> Result<Self, MaskError> {
+        let (h, w) = (height as usize, width as usize);
+        if bitmap.len() != h * w {
+            return Err(MaskError::BitmapSize {
+                expected: h * w,
+                actual: bitmap.len(),
+            });
+        }
+        let mut counts = Vec::new();
+        let (mut value, mut run) = (false, 0u32);
+        for x in 0..w {
+            for y in 0..h {
+                if bitmap[y * w + x] != value {
+                    counts.push(run);
+                    value = !value;
+                    run = 0;
+                }
+                run += 1;
+            }
+        }
+        counts.push(run);
+        Ok(Self {
+            height,
+            width,
+            counts,
+        })
+    }
+
+    /// Decode a compressed COCO counts string, as produced by
+    /// `pycocotools.mask.encode`.
+    pub fn from_coco_string(height: u32, width: u32, counts: &str) -> Result<Self, MaskError> {
+        let bytes = counts.as_bytes();
+        let mut decoded: Vec<i64> = Vec::new();
+        let mut p = 0;
+        while p < bytes.len() {
+            let (mut x, mut k) = (0i64, 0);
+            loop {
+                let c = *bytes.get(p).ok_or(MaskError::InvalidString)? as i64 - 48;
+                if !(0..64).contains(&c) || k >= 12 {
+                    return Err(MaskError::InvalidString);
+                }
+                x |= (c & 0x1f) << (5 * k);
+                p += 1;
+                k += 1;
+                if c & 0x20 == 0 {
+                    if c & 0x10 != 0 {
+                        x |= -1i64 << (5 * k);
+                    }
+                    break;
+                }
+            }
+            if decoded.len() > 2 {
+                x = x
+                    .checked_add(decoded[decoded.len() - 2])
+                    .ok_or(MaskError::InvalidString)?;
+            }
+            decoded.push(x);
+        }
+        let counts = decoded
+            .into_iter()
+            .map(|c| u32::try_from(c).map_err(|_| MaskError::InvalidString))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(height, width, counts)
+    }
+
+    /// Encode as a compressed COCO counts string.
+    pub fn to_coco_string(&self) -> String {
+        let mut out = String::new();
+        for (i, &count) in self.counts.iter().enumerate() {
+            let mut x = count as i64;
+            if i > 2 {
+                x -= self.counts[i - 2] as i64;
+            }
+            loop {
+                let mut c = x & 0x1f;
+                x >>= 5;
+                let more = if c & 0x10 != 0 { x != -1 } else { x != 0 };
+                if more {
+                    c |= 0x20;
+                }
+                out.push((c + 48) as u8 as char);
+                if !more {
+                    break;
+                }
+            }
+        }
+        out
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Uncompressed run lengths.
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+
+    /// Number of foreground pixels.
+    pub fn area(&self) -> u64 {
+        self.counts
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .map(|&c| c as u64)
+            .sum()
+    }
+
+    /// Foreground pixels shared with `other`, found by walking both run
+    /// lists without decoding. Masks of different sizes share none.
+    pub fn intersection(&self, other: &RleMask) -> u64 {
+        if (self.height, self.width) != (other.height, other.width) {
+            return 0;
+        }
+        let (a, b) = (&self.counts, &other.counts);
+        let (mut ia, mut ib) = (0, 0);
+        let (mut ra, mut rb) = (
+            a.first().copied().unwrap_or(0),
+            b.first().copied().unwrap_or(0),
+        );
+        let mut inter = 0u64;
+        while ia < a.len() && ib < b.len() {
+            let step = ra.min(rb);
+            // Odd runs are foreground.
+            if ia % 2 == 1 && ib % 2 == 1 {
+                inter += step as u64;
+            }
+            ra -= step;
+            rb -= step;
+            if ra == 0 {
+                ia += 1;
+                ra = a.get(ia).copied().unwrap_or(0);
+            }
+            if rb == 0 {
+                ib += 1;
+                rb = b.get(ib).copied().unwrap_or(0);
+            }
+        }
+        inter
+    }
+
+    /// Intersection over union with `other`; zero if both are empty or the
+    /// sizes differ.
+    pub fn iou(&self, other: &RleMask) -> f32 {
+        let inter = self.intersection(other);
+        let union = self.area() + other.area() - inter;
+        if union > 0 {
+            (inter as f64 / union as f64) as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// Tight box around the foreground pixels, or `None` if the mask is
+    /// empty.
+    pub fn bounding_rect(&self) -> Option<Rect> {
+        let h = self.height as u64;
+        let (mut x1, mut y1, mut x2, mut y2) = (u64::MAX, u64::MAX, 0, 0);
+        let mut pos = 0u64;
+        for (i, &count) in self.counts.iter().enumerate() {
+            let (start, end) = (pos, pos + count as u64);
+            pos = end;
+            if i % 2 == 0 || count == 0 {
+                continue;
+            }
+            let (first_col, last_col) = (start / h, (end - 1) / h);
+            x1 = x1.min(first_col);
+            x2 = x2.max(last_col);
+            if first_col == last_col {
+                y1 = y1.min(start % h);
+                y2 = y2.max((end - 1) % h);
+            } else {
+                // The run wraps past a column end, so covers every row.
+                y1 = 0;
+                y2 = h - 1;
+            }
+        }
+        (x1 != u64::MAX)
+            .then(|| Rect::from_tlbr(x1 as f32, y1 as f32, (x2 + 1) as f32, (y2 + 1) as f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Row-major bitmap with a filled rectangle.
+    fn rect_bitmap(h: usize, w: usize, x: usize, y: usize, rw: usize, rh: usize) -> Vec<bool> {
+        (0..h * w)
+            .map(|i| {
+                let (r, c) = (i / w, i % w);
+                (y..y + rh).contains(&r) && (x..x + rw).contains(&c)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_from_bitmap_and_area() {
+        // 3x2 bitmap, column-major order: 0 1 1 | 0 0 1.
+        let mask = RleMask::from_bitmap(3, 2, &[false, false, true, false, true, true]).unwrap();
+        assert_eq!(mask.counts(), &[1, 2, 2, 1]);
+        assert_eq!(mask.area(), 3);
+        assert!(RleMask::new(3, 2, vec![1, 2]).is_err());
+    }
+
+    #[test]
+    fn test_iou_matches_bitmap() {
+        let a = rect_bitmap(40, 50, 5, 5, 20, 10);
+        let b = rect_bitmap(40, 50, 15, 8, 20, 20);
+        let ma = RleMask::from_bitmap(40, 50, &a).unwrap();
+        let mb = RleMask::from_bitmap(40, 50, &b).unwrap();
+
+        let inter = a.iter().zip(&b).filter(|(x, y)| **x && **y).count() as u64;
+        let union = a.iter().zip(&b).filter(|(x, y)| **x || **y).count() as u64;
+        assert_eq!(ma.intersection(&mb), inter);
+        assert!((ma.iou(&mb) - inter as f32 / union as f32).abs() < 1e-6);
+        assert!((ma.iou(&ma) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_coco_string_round_trip() {
+        let bitmap = rect_bitmap(30, 40, 3, 7, 25, 12);
+        let mask = RleMask::from_bitmap(30, 40, &bitmap).unwrap();
+        let encoded = mask.to_coco_string();
+        assert_eq!(RleMask::from_coco_string(30, 40, &encoded).unwrap(), mask);
+
+        // Reference string from pycocotools for a 4x4 mask with a 2x2
+        // square at rows 1-2, columns 1-2.
+        let square = RleMask::from_coco_string(4, 4, "52203").unwrap();
+        assert_eq!(square.counts(), &[5, 2, 2, 2, 5]);
+        assert_eq!(square.to_coco_string(), "52203");
+        assert!(RleMask::from_coco_string(4, 4, "5220").is_err());
+        assert!(RleMask::from_coco_string(4, 4, &"ooooooooooo?".repeat(40)).is_err());
+    }
+
+    #[test]
+    fn test_bounding_rect() {
+        let bitmap = rect_bitmap(30, 40, 3, 7, 25, 12);
+        let mask = RleMask::from_bitmap(30, 40, &bitmap).unwrap();
+        assert_eq!(
+            mask.bounding_rect().unwrap().to_tlwh(),
+            [3.0, 7.0, 25.0, 12.0]
+        );
+        assert!(
+            RleMask::new(2, 2, vec![4])
+                .unwrap()
+                .bounding_rect()
+                .is_none()
+        );
+    }
+}
@@ -1,15 +1,25 @@
 //! Matching utilities for multi-object tracking.
 
+use crate::tracker::assignment::{AssignmentError, AssignmentSolver};
+use crate::tracker::geometry::{Affinity, BoxGeometry};
+use crate::tracker::keypoints::Keypoint;
+use crate::tracker::mask::RleMask;
 use crate::tracker::rect::Rect;
 use ndarray::Array2;
 
 /// Detection input for the tracker.
 #[derive(Debug, Clone)]
-pub struct Detection {
-    /// Bounding box in TLBR format (x1, y1, x2, y2)
-    pub bbox: Rect,
+pub struct Detection<B: BoxGeometry = Rect> {
+    /// Bounding box (stored as TLWH for `Rect`)
+    pub bbox: B,
     /// Detection confidence score
     pub score: f32,
+    /// Class ID (optional, for multi-class detection)
+    pub class_id: Option<usize>,
+    /// Instance segmentation mask (optional)
+    pub mask: Option<RleMask>,
+    /// Pose keypoints with per-keypoint confidence (optional)
+    pub keypoints: Option<Vec<Keypoint>>,
 }
 
 impl Detection {
@@ -17,20 +27,54 @@ impl Detection {
         Self {
             bbox: Rect::from_tlbr(x1, y1, x2, y2),
             score,
+            class_id: None,
+            mask: None,
+            keypoints: None,
         }
     }
+}
+
+impl<B: BoxGeometry> Detection<B> {
+    pub fn from_rect(bbox: B, score: f32) -> Self {
+        Self {
+            bbox,
+            score,
+            class_id: None,
+            mask: None,
+            keypoints: None,
+        }
+    }
+
+    /// Set the class ID of this detection.
+    pub fn with_class_id(mut self, class_id: usize) -> Self {
+        self.class_id = Some(class_id);
+        self
+    }
+
+    /// Attach an instance segmentation mask to this detection.
+    pub fn with_mask(mut self, mask: RleMask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
 
-    pub fn from_rect(bbox: Rect, score: f32) -> Self {
-        Self { bbox, score }
+    /// Attach pose keypoints to this detection.
+    pub fn with_keypoints(mut self, keypoints: Vec<Keypoint>) -> Self {
+        self.keypoints = Some(keypoints);
+        self
     }
 }
 
-/// Compute IoU distance matrix between tracks and detections.
-pub fn iou_distance(track_boxes: &[Rect], det_boxes: &[Rect]) -> Array2<f32> {
+/// Compute the distance matrix (`1 - affinity`, IoU distance by default)
+/// between tracks and detections.
+pub fn affinity_distance<B: BoxGeometry>(
+    track_boxes: &[B],
+    det_boxes: &[B],
+    affinity: Affinity,
+) -> Array2<f32> {
     let mut dists = Array2::zeros((track_boxes.len(), det_boxes.len()));
     for (i, t) in track_boxes.iter().enumerate() {
         for (j, d) in det_boxes.iter().enumerate() {
-            dists[[i, j]] = 1.0 - t.iou(d);
+            dists[[i, j]] = 1.0 - t.affinity(d, affinity);
         }
     }
     dists
@@ -43,57 +87,47 @@ pub struct AssignmentResult {
     pub unmatched_detections: Vec<usize>,
 }
 
-pub fn linear_assignment(cost_matrix: &Array2<f32>, thresh: f32) -> AssignmentResult {
+/// Assign tracks (rows) to detections (columns), keeping only pairs whose
+/// cost is at most `thresh`.
+pub fn linear_assignment<S: AssignmentSolver + ?Sized>(
+    cost_matrix: &Array2<f32>,
+    thresh: f32,
+    solver: &S,
+) -> Result<AssignmentResult, AssignmentError> {
     let (num_rows, num_cols) = cost_matrix.dim();
 
     if num_rows == 0 {
-        return AssignmentResult {
+        return Ok(AssignmentResult {
             matches: vec![],
             unmatched_tracks: vec![],
             unmatched_detections: (0..num_cols).collect(),
-        };
+        });
     }
 
     if num_cols == 0 {
-        return AssignmentResult {
+        return Ok(AssignmentResult {
             matches: vec![],
             unmatched_tracks: (0..num_rows).collect(),
             unmatched_detections: vec![],
-        };
+        });
     }
 
-    let size = num_rows.max(num_cols);
-    let mut padded = Array2::<f64>::from_elem((size, size), 1e6);
-
-    for i in 0..num_rows {
-        for j in 0..num_cols {
-            padded[[i, j]] = cost_matrix[[i, j]] as f64;
-        }
+    if let Some(((row, col), &value)) = cost_matrix.indexed_iter().find(|(_, c)| !c.is_finite()) {
+        return Err(AssignmentError::InvalidCost { row, col, value });
     }
 
-    let result = lapjv::lapjv(&padded);
+    let row_to_col = solver.solve(cost_matrix)?;
     let mut matches = vec![];
     let mut unmatched_tracks = vec![];
     let mut unmatched_detections_mask: Vec<bool> = vec![true; num_cols];
 
-    match result {
-        Ok((row_to_col, _)) => {
-            for (row_idx, &col_idx) in row_to_col.iter().enumerate() {
-                if row_idx >= num_rows {
-                    continue;
-                }
-                if col_idx >= num_cols {
-                    unmatched_tracks.push(row_idx);
-                } else if cost_matrix[[row_idx, col_idx]] <= thresh {
-                    matches.push((row_idx, col_idx));
-                    unmatched_detections_mask[col_idx] = false;
-                } else {
-                    unmatched_tracks.push(row_idx);
-                }
+    for (row_idx, col) in row_to_col.into_iter().enumerate() {
+        match col {
+            Some(col_idx) if cost_matrix[[row_idx, col_idx]] <= thresh => {
+                matches.push((row_idx, col_idx));
+                unmatched_detections_mask[col_idx] = false;
             }
-        }
-        Err(_) => {
-            unmatched_tracks = (0..num_rows).collect();
+            _ => unmatched_tracks.push(row_idx),
         }
     }
 
@@ -103,14 +137,14 @@ pub fn linear_assignment(cost_matrix: &Array2<f32>, thresh: f32) -> AssignmentRe
         .filter_map(|(i, &u)| if u { Some(i) } else { None })
         .collect();
 
-    AssignmentResult {
+    Ok(AssignmentResult {
         matches,
         unmatched_tracks,
         unmatched_detections,
-    }
+    })
 }
 
-pub fn fuse_score(cost_matrix: &mut Array2<f32>, detections: &[Detection]) {
+pub fn fuse_score<B: BoxGeometry>(cost_matrix: &mut Array2<f32>, detections: &[Detection<B>]) {
     let (rows, cols) = cost_matrix.dim();
     for i in 0..rows {
         for j in 0..cols {
@@ -0,0 +1,187 @@
+//! Typed views of a track's Kalman state: velocity, uncertainty and
+//! forecasts.
+
+use ndarray::{Array1, Array2};
+
+use crate::tracker::geometry::BoxGeometry;
+use crate::tracker::rect::Rect;
+use crate::tracker::strack::STrack;
+
+/// A 2D velocity in a box's center coordinates: image coordinates (y points
+/// down) for 2D boxes, the ground plane for `Box3D`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Velocity {
+    pub vx: f32,
+    pub vy: f32,
+}
+
+impl Velocity {
+    pub fn new(vx: f32, vy: f32) -> Self {
+        Self { vx, vy }
+    }
+
+    /// Magnitude of the velocity.
+    pub fn speed(&self) -> f32 {
+        self.vx.hypot(self.vy)
+    }
+
+    /// Direction of motion in radians, `atan2(vy, vx)`, in `(-pi, pi]`.
+    ///
+    /// `0` points right and positive angles turn clockwise on screen,
+    /// because image y grows downwards.
+    pub fn heading(&self) -> f32 {
+        self.vy.atan2(self.vx)
+    }
+
+    /// Scale both components, e.g. by a frame rate to get per-second values.
+    pub fn scaled(&self, factor: f32) -> Self {
+        Self::new(self.vx * factor, self.vy * factor)
+    }
+}
+
+/// An uncertainty ellipse derived from a 2x2 covariance block.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct UncertaintyEllipse {
+    /// Ellipse center.
+    pub center: (f32, f32),
+    /// Semi-axis along `angle`.
+    pub semi_major: f32,
+    /// Semi-axis perpendicular to `angle`.
+    pub semi_minor: f32,
+    /// Orientation of the major axis in radians, measured like `Velocity::heading`.
+    pub angle: f32,
+}
+
+impl UncertaintyEllipse {
+    /// Build the `n_sigma` ellipse of a 2D Gaussian with covariance
+    /// `[[xx, xy], [xy, yy]]` centered at `center`.
+    pub fn from_covariance(center: (f32, f32), xx: f64, xy: f64, yy: f64, n_sigma: f32) -> Self {
+        // Eigenvalues of a symmetric 2x2 matrix.
+        let mean = (xx + yy) / 2.0;
+        let diff = ((xx - yy) / 2.0).hypot(xy);
+        let major = (mean + diff).max(0.0);
+        let minor = (mean - diff).max(0.0);
+        let angle = 0.5 * (2.0 * xy).atan2(xx - yy);
+        Self {
+            center,
+            semi_major: n_sigma * major.sqrt() as f32,
+            semi_minor: n_sigma * minor.sqrt() as f32,
+            angle: angle as f32,
+        }
+    }
+
+    /// Area of the ellipse.
+    pub fn area(&self) -> f32 {
+        std::f32::consts::PI * self.semi_major * self.semi_minor
+    }
+}
+
+/// A track's predicted state some steps into the future.
+#[derive(Debug, Clone)]
+pub struct Forecast<B: BoxGeometry = Rect> {
+    /// Number of frames ahead of the track's current state.
+    pub steps_ahead: u32,
+    /// Predicted bounding box.
+    pub rect: B,
+    /// Predicted Kalman state mean (`2 * B::DIM`, 8-dim XYAH plus
+    /// velocities for `Rect`).
+    pub mean: Array1<f64>,
+    /// Predicted Kalman state covariance (`2 * B::DIM` square, 8x8 for
+    /// `Rect`).
+    pub covariance: Array2<f64>,
+}
+
+impl<B: BoxGeometry> Forecast<B> {
+    /// `n_sigma` uncertainty ellipse of the predicted box center.
+    pub fn position_uncertainty(&self, n_sigma: f32) -> UncertaintyEllipse {
+        UncertaintyEllipse::from_covariance(
+            (self.mean[0] as f32, self.mean[1] as f32),
+            self.covariance[[0, 0]],
+            self.covariance[[0, 1]],
+            self.covariance[[1, 1]],
+            n_sigma,
+        )
+    }
+}
+
+/// Frames until the boxes of two tracks first overlap, assuming both keep
+/// their current center velocity and size.
+///
+/// Returns `Some(0.0)` if they already overlap and `None` if they never will
+/// or either track has no motion state.
+pub fn time_to_collision<B: BoxGeometry>(a: &STrack<B>, b: &STrack<B>) -> Option<f32> {
+    let (va, vb) = (a.velocity()?, b.velocity()?);
+    let (ra, rb) = (a.rect(), b.rect());
+    let (ca, cb) = (ra.center(), rb.center());
+
+    // Per axis, the boxes overlap while |d + v t| < half-extent sum.
+    let axis = |d: f32, v: f32, reach: f32| -> Option<(f32, f32)> {
+        if v == 0.0 {
+            return (d.abs() < reach).then_some((f32::NEG_INFINITY, f32::INFINITY));
+        }
+        let (t1, t2) = ((-reach - d) / v, (reach - d) / v);
+        Some((t1.min(t2), t1.max(t2)))
+    };
+    let (x0, x1) = axis(cb.0 - ca.0, vb.vx - va.vx, (ra.width + rb.width) / 2.0)?;
+    let (y0, y1) = axis(cb.1 - ca.1, vb.vy - va.vy, (ra.height + rb.height) / 2.0)?;
+
+    let start = x0.max(y0).max(0.0);
+    let end = x1.min(y1);
+    (start < end).then_some(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::kalman_filter::KalmanFilter;
+
+    /// An activated track with the given box and a fixed center velocity.
+    fn moving_track(rect: Rect, vx: f64, vy: f64) -> STrack {
+        let mut track = STrack::new(rect, 0.9);
+        track.activate(&KalmanFilter::default(), 1);
+        let mean = track.mean.as_mut().unwrap();
+        mean[4] = vx;
+        mean[5] = vy;
+        track
+    }
+
+    #[test]
+    fn test_time_to_collision() {
+        let a = moving_track(Rect::new(0.0, 0.0, 10.0, 10.0), 2.0, 0.0);
+        let b = moving_track(Rect::new(50.0, 0.0, 10.0, 10.0), -2.0, 0.0);
+        // Gap of 40 pixels closing at 4 pixels per frame.
+        assert!((time_to_collision(&a, &b).unwrap() - 10.0).abs() < 1e-4);
+
+        let c = moving_track(Rect::new(50.0, 100.0, 10.0, 10.0), -2.0, 0.0);
+        assert_eq!(time_to_collision(&a, &c), None);
+
+        let d = moving_track(Rect::new(5.0, 5.0, 10.0, 10.0), 0.0, 0.0);
+        assert_eq!(time_to_collision(&a, &d), Some(0.0));
+
+        // Moving apart.
+        let e = moving_track(Rect::new(50.0, 0.0, 10.0, 10.0), 3.0, 0.0);
+        assert_eq!(time_to_collision(&a, &e), None);
+    }
+
+    #[test]
+    fn test_velocity_heading() {
+        let v = Velocity::new(3.0, 4.0);
+        assert_eq!(v.speed(), 5.0);
+        assert!((Velocity::new(0.0, 2.0).heading() - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert_eq!(v.scaled(30.0), Velocity::new(90.0, 120.0));
+    }
+
+    #[test]
+    fn test_ellipse_from_covariance() {
+        let axis_aligned = UncertaintyEllipse::from_covariance((1.0, 2.0), 4.0, 0.0, 1.0, 2.0);
+        assert_eq!(axis_aligned.semi_major, 4.0);
+        assert_eq!(axis_aligned.semi_minor, 2.0);
+        assert_eq!(axis_aligned.angle, 0.0);
+
+        // Correlated x and y: the major axis lies on the diagonal.
+        let diagonal = UncertaintyEllipse::from_covariance((0.0, 0.0), 2.0, 1.0, 2.0, 1.0);
+        assert!((diagonal.semi_major - 3f32.sqrt()).abs() < 1e-6);
+        assert!((diagonal.semi_minor - 1.0).abs() < 1e-6);
+        assert!((diagonal.angle - std::f32::consts::FRAC_PI_4).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,186 @@
+//! Image-space polygons and box anchor points.
+
+use crate::tracker::rect::Rect;
+
+/// Point on a bounding box used to place a track in the image.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Anchor {
+    /// Middle of the bottom edge, where a pedestrian or vehicle meets the
+    /// ground.
+    #[default]
+    BottomCenter,
+    /// Box center.
+    Center,
+    /// Middle of the top edge.
+    TopCenter,
+}
+
+impl Anchor {
+    /// Image coordinates of this anchor on `rect`.
+    pub fn point(self, rect: &Rect) -> (f32, f32) {
+        let cx = rect.x + rect.width / 2.0;
+        match self {
+            Anchor::BottomCenter => (cx, rect.y + rect.height),
+            Anchor::Center => (cx, rect.y + rect.height / 2.0),
+            Anchor::TopCenter => (cx, rect.y),
+        }
+    }
+}
+
+/// Simple (non-self-intersecting) polygon in image coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    vertices: Vec<(f32, f32)>,
+}
+
+impl Polygon {
+    /// Create a polygon from its vertices, in either winding order.
+    pub fn new(vertices: Vec<(f32, f32)>) -> Self {
+        Self { vertices }
+    }
+
+    /// Axis-aligned rectangle as a polygon.
+    pub fn from_rect(rect: &Rect) -> Self {
+        let [x1, y1, x2, y2] = rect.to_tlbr();
+        Self::new(vec![(x1, y1), (x2, y1), (x2, y2), (x1, y2)])
+    }
+
+    pub fn vertices(&self) -> &[(f32, f32)] {
+        &self.vertices
+    }
+
+    /// Polygon edges as `(start, end)` pairs, closing back to the first
+    /// vertex.
+    pub fn edges(&self) -> impl Iterator<Item = ((f32, f32), (f32, f32))> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// Unsigned area (shoelace formula).
+    pub fn area(&self) -> f32 {
+        self.signed_area().abs()
+    }
+
+    /// Signed area: positive for counter-clockwise vertices in a y-up frame.
+    pub fn signed_area(&self) -> f32 {
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+            .sum::<f32>()
+            / 2.0
+    }
+
+    /// Whether `point` lies inside the polygon (even-odd rule).
+    pub fn contains(&self, (px, py): (f32, f32)) -> bool {
+        let mut inside = false;
+        for ((x1, y1), (x2, y2)) in self.edges() {
+            if (y1 > py) != (y2 > py) && px < x1 + (py - y1) / (y2 - y1) * (x2 - x1) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Shortest distance from `point` to the polygon boundary.
+    pub fn distance_to_boundary(&self, point: (f32, f32)) -> f32 {
+        self.edges()
+            .map(|(a, b)| segment_distance(point, a, b))
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    /// Intersection with a convex polygon (Sutherland-Hodgman clipping).
+    /// `self` may be any simple polygon; the result is empty if they don't
+    /// overlap.
+    pub fn clip_convex(&self, clip: &Polygon) -> Polygon {
+        let orientation = clip.signed_area().signum();
+        let inside = |p: (f32, f32), a: (f32, f32), b: (f32, f32)| {
+            ((b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)) * orientation >= 0.0
+        };
+
+        let mut output = self.vertices.clone();
+        for (a, b) in clip.edges() {
+            if output.is_empty() {
+                break;
+            }
+            let input = std::mem::take(&mut output);
+            for i in 0..input.len() {
+                let (p, q) = (input[i], input[(i + 1) % input.len()]);
+                let (p_in, q_in) = (inside(p, a, b), inside(q, a, b));
+                if p_in {
+                    output.push(p);
+                }
+                if p_in != q_in {
+                    output.push(line_intersection(p, q, a, b));
+                }
+            }
+        }
+        Polygon::new(output)
+    }
+}
+
+/// Intersection of segment `p`-`q` with the infinite line through `a`-`b`.
+fn line_intersection(p: (f32, f32), q: (f32, f32), a: (f32, f32), b: (f32, f32)) -> (f32, f32) {
+    let side = |v: (f32, f32)| (b.0 - a.0) * (v.1 - a.1) - (b.1 - a.1) * (v.0 - a.0);
+    let (sp, sq) = (side(p), side(q));
+    let t = sp / (sp - sq);
+    (p.0 + t * (q.0 - p.0), p.1 + t * (q.1 - p.1))
+}
+
+/// Distance from `p` to the segment `a`-`b`.
+pub(crate) fn segment_distance(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (cx, cy) = (a.0 + t * dx, a.1 + t * dy);
+    ((p.0 - cx).powi(2) + (p.1 - cy).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_points() {
+        let rect = Rect::new(10.0, 20.0, 40.0, 60.0);
+        assert_eq!(Anchor::BottomCenter.point(&rect), (30.0, 80.0));
+        assert_eq!(Anchor::Center.point(&rect), (30.0, 50.0));
+        assert_eq!(Anchor::TopCenter.point(&rect), (30.0, 20.0));
+    }
+
+    #[test]
+    fn test_clip_convex() {
+        let a = Polygon::from_rect(&Rect::new(0.0, 0.0, 10.0, 10.0));
+        let b = Polygon::from_rect(&Rect::new(5.0, 5.0, 10.0, 10.0));
+        assert!((a.clip_convex(&b).area() - 25.0).abs() < 1e-4);
+
+        // Clockwise clip polygon and a diamond inside the square.
+        let reversed = Polygon::new(b.vertices().iter().rev().copied().collect());
+        assert!((a.clip_convex(&reversed).area() - 25.0).abs() < 1e-4);
+        let diamond = Polygon::new(vec![(5.0, 0.0), (10.0, 5.0), (5.0, 10.0), (0.0, 5.0)]);
+        assert!((diamond.clip_convex(&a).area() - 50.0).abs() < 1e-4);
+
+        let far = Polygon::from_rect(&Rect::new(50.0, 50.0, 10.0, 10.0));
+        assert_eq!(a.clip_convex(&far).area(), 0.0);
+    }
+
+    #[test]
+    fn test_polygon_contains_and_distance() {
+        // L-shaped polygon.
+        let polygon = Polygon::new(vec![
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 5.0),
+            (5.0, 5.0),
+            (5.0, 10.0),
+            (0.0, 10.0),
+        ]);
+        assert!(polygon.contains((2.0, 8.0)));
+        assert!(!polygon.contains((8.0, 8.0)));
+        assert_eq!(polygon.area(), 75.0);
+        assert!((polygon.distance_to_boundary((8.0, 8.0)) - 3.0).abs() < 1e-6);
+        assert!((polygon.distance_to_boundary((2.0, 2.0)) - 2.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,275 @@
+//! TrackerPool for updating many independent trackers in parallel.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::tracker::byte_tracker::{BYTETracker, TrackerConfig};
+use crate::tracker::matching::Detection;
+use crate::tracker::stats::TrackerStats;
+use crate::tracker::strack::STrack;
+
+/// Aggregate statistics over all streams of a `TrackerPool`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PoolStats {
+    /// Streams currently in the pool.
+    pub streams: usize,
+    /// Batches processed with `update`.
+    pub batches: u64,
+    /// Streams created, including those added with `add_stream`.
+    pub streams_created: u64,
+    /// Streams removed after being idle for too long.
+    pub streams_expired: u64,
+    /// Tracker statistics summed over all current streams.
+    pub trackers: TrackerStats,
+}
+
+struct PoolEntry {
+    tracker: BYTETracker,
+    last_batch: u64,
+}
+
+/// A stream's tracker together with its frames from one batch, each tagged
+/// with its index in the batch. The stream ID is kept out of the group so
+/// that it needn't be `Send` for the parallel update.
+struct StreamGroup {
+    entry: PoolEntry,
+    frames: Vec<(usize, Vec<Detection>)>,
+}
+
+/// Many independent `BYTETracker`s keyed by stream ID.
+///
+/// Each call to `update` takes detections for any subset of streams and
+/// updates the corresponding trackers, in parallel when the `rayon` feature
+/// is enabled. Trackers are created the first time a stream ID is seen and,
+/// if an idle expiry is set, dropped after that many batches without frames.
+pub struct TrackerPool<K: Eq + Hash + Clone = u32> {
+    config: TrackerConfig,
+    streams: HashMap<K, PoolEntry>,
+    max_idle_batches: Option<u64>,
+    batches: u64,
+    streams_created: u64,
+    streams_expired: u64,
+}
+
+impl<K: Eq + Hash + Clone> TrackerPool<K> {
+    /// Create a new pool. New streams' trackers use `config`.
+    pub fn new(config: TrackerConfig) -> Self {
+        Self {
+            config,
+            streams: HashMap::new(),
+            max_idle_batches: None,
+            batches: 0,
+            streams_created: 0,
+            streams_expired: 0,
+        }
+    }
+
+    /// Create a new pool with default tracker configuration.
+    pub fn with_default_config() -> Self {
+        Self::new(TrackerConfig::default())
+    }
+
+    /// Remove streams that received no frames for `batches` consecutive
+    /// `update` calls. With `0`, every stream is removed after each call.
+    pub fn with_idle_expiry(mut self, batches: u64) -> Self {
+        self.max_idle_batches = Some(batches);
+        self
+    }
+
+    /// Update the trackers of all streams in the batch.
+    ///
+    /// A stream may appear more than once; its frames are then applied in
+    /// order.
+    ///
+    /// # Returns
+    /// Active tracks per entry, in the same order as `batch`.
+    pub fn update(&mut self, batch: Vec<(K, Vec<Detection>)>) -> Vec<(K, Vec<STrack>)> {
+        self.batches += 1;
+        let len = batch.len();
+
+        // Take each stream's tracker out of the map so they can be updated
+        // independently, grouping repeated stream IDs together.
+        let mut stream_ids: Vec<K> = Vec::new();
+        let mut groups: Vec<StreamGroup> = Vec::new();
+        let mut group_of: HashMap<K, usize> = HashMap::new();
+        for (index, (stream_id, detections)) in batch.into_iter().enumerate() {
+            if let Some(&g) = group_of.get(&stream_id) {
+                groups[g].frames.push((index, detections));
+                continue;
+            }
+            let entry = match self.streams.remove(&stream_id) {
+                Some(entry) => entry,
+                None => {
+                    self.streams_created += 1;
+                    PoolEntry {
+                        tracker: BYTETracker::new(self.config.clone()),
+                        last_batch: 0,
+                    }
+                }
+            };
+            group_of.insert(stream_id.clone(), groups.len());
+            stream_ids.push(stream_id);
+            groups.push(StreamGroup {
+                entry,
+                frames: vec![(index, detections)],
+            });
+        }
+
+        let batch_id = self.batches;
+        let run = |group: &mut StreamGroup| {
+            group.entry.last_batch = batch_id;
+            group
+                .frames
+                .drain(..)
+                .map(|(index, detections)| (index, group.entry.tracker.update(detections)))
+                .collect::<Vec<_>>()
+        };
+        #[cfg(feature = "rayon")]
+        let results: Vec<Vec<(usize, Vec<STrack>)>> = groups.par_iter_mut().map(run).collect();
+        #[cfg(not(feature = "rayon"))]
+        let results: Vec<Vec<(usize, Vec<STrack>)>> = groups.iter_mut().map(run).collect();
+
+        let mut output: Vec<Option<(K, Vec<STrack>)>> = (0..len).map(|_| None).collect();
+        for ((stream_id, group), tracks) in stream_ids.into_iter().zip(groups).zip(results) {
+            for (index, tracks) in tracks {
+                output[index] = Some((stream_id.clone(), tracks));
+            }
+            self.streams.insert(stream_id, group.entry);
+        }
+
+        self.expire_idle();
+        output.into_iter().flatten().collect()
+    }
+
+    /// Drop streams that have been idle for the expiry.
+    fn expire_idle(&mut self) {
+        let Some(max_idle) = self.max_idle_batches else {
+            return;
+        };
+        let now = self.batches;
+        let before = self.streams.len();
+        self.streams
+            .retain(|_, entry| now.saturating_sub(entry.last_batch) < max_idle);
+        self.streams_expired += (before - self.streams.len()) as u64;
+    }
+
+    /// Register a stream with a tracker using the given configuration.
+    ///
+    /// Replaces any existing tracker for the stream.
+    pub fn add_stream(&mut self, stream_id: K, config: TrackerConfig) {
+        self.streams_created += 1;
+        self.streams.insert(
+            stream_id,
+            PoolEntry {
+                tracker: BYTETracker::new(config),
+                last_batch: self.batches,
+            },
+        );
+    }
+
+    /// Remove a stream and return its tracker, if it existed.
+    pub fn remove_stream(&mut self, stream_id: &K) -> Option<BYTETracker> {
+        self.streams.remove(stream_id).map(|entry| entry.tracker)
+    }
+
+    /// Iterate over the IDs of all streams in the pool.
+    pub fn stream_ids(&self) -> impl Iterator<Item = &K> {
+        self.streams.keys()
+    }
+
+    /// Number of streams in the pool.
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Whether the pool has no streams.
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    /// Get a reference to the tracker of a stream.
+    pub fn tracker(&self, stream_id: &K) -> Option<&BYTETracker> {
+        self.streams.get(stream_id).map(|entry| &entry.tracker)
+    }
+
+    /// Get a mutable reference to the tracker of a stream.
+    pub fn tracker_mut(&mut self, stream_id: &K) -> Option<&mut BYTETracker> {
+        self.streams
+            .get_mut(stream_id)
+            .map(|entry| &mut entry.tracker)
+    }
+
+    /// Aggregate statistics over the pool and all current streams.
+    pub fn stats(&self) -> PoolStats {
+        let mut trackers = TrackerStats::default();
+        for entry in self.streams.values() {
+            trackers.merge(&entry.tracker.stats());
+        }
+        PoolStats {
+            streams: self.streams.len(),
+            batches: self.batches,
+            streams_created: self.streams_created,
+            streams_expired: self.streams_expired,
+            trackers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn det(x: f32) -> Detection {
+        Detection::new(x, 10.0, x + 40.0, 90.0, 0.9)
+    }
+
+    #[test]
+    fn test_pool_updates_streams_independently() {
+        let mut pool = TrackerPool::with_default_config();
+
+        let out = pool.update(vec![
+            ("cam-a", vec![det(100.0)]),
+            ("cam-b", vec![det(300.0), det(500.0)]),
+            ("cam-a", vec![det(102.0)]),
+        ]);
+
+        assert_eq!(out.len(), 3);
+        assert_eq!(out[0].0, "cam-a");
+        assert_eq!(out[1].1.len(), 2);
+        assert_eq!(out[2].1[0].track_id, out[0].1[0].track_id);
+        assert_eq!(pool.tracker(&"cam-a").unwrap().frame_id(), 2);
+        assert_eq!(pool.tracker(&"cam-b").unwrap().frame_id(), 1);
+
+        let stats = pool.stats();
+        assert_eq!(stats.streams, 2);
+        assert_eq!(stats.streams_created, 2);
+        assert_eq!(stats.trackers.frames, 3);
+        assert_eq!(stats.trackers.tracked, 3);
+    }
+
+    #[test]
+    fn test_pool_idle_expiry() {
+        let mut pool = TrackerPool::with_default_config().with_idle_expiry(2);
+
+        pool.update(vec![(1, vec![det(0.0)]), (2, vec![det(0.0)])]);
+        pool.update(vec![(1, vec![det(0.0)])]);
+        assert_eq!(pool.len(), 2);
+
+        // Stream 2 has now been idle for two batches.
+        pool.update(vec![(1, vec![det(0.0)])]);
+        assert_eq!(pool.len(), 1);
+        assert!(pool.tracker(&2).is_none());
+        assert_eq!(pool.stats().streams_expired, 1);
+    }
+
+    #[test]
+    fn test_pool_accepts_non_send_keys() {
+        let key: std::rc::Rc<str> = "cam-a".into();
+        let mut pool = TrackerPool::with_default_config();
+        let out = pool.update(vec![(key.clone(), vec![det(100.0)])]);
+        assert_eq!(out[0].0, key);
+    }
+}
@@ -88,6 +88,15 @@ impl Rect {
         self.width * self.height
     }
 
+    /// Clip to the image `[0, width] x [0, height]`. Boxes entirely outside
+    /// the image get zero width or height.
+    pub fn clip(&self, width: f32, height: f32) -> Rect {
+        let [x1, y1, x2, y2] = self.to_tlbr();
+        let (x1, x2) = (x1.clamp(0.0, width), x2.clamp(0.0, width));
+        let (y1, y2) = (y1.clamp(0.0, height), y2.clamp(0.0, height));
+        Rect::from_tlbr(x1, y1, x2.max(x1), y2.max(y1))
+    }
+
     /// Calculate Intersection over Union (IoU) with another bounding box.
     pub fn iou(&self, other: &Rect) -> f32 {
         let x1 = self.x.max(other.x);
@@ -111,11 +120,13 @@ impl Rect {
 
 use ndarray::Array2;
 
+use crate::tracker::geometry::BoxGeometry;
+
 /// Calculate IoU matrix between two sets of bounding boxes.
 ///
 /// Returns a matrix of shape (M, N) where M is the length of `boxes_a`
 /// and N is the length of `boxes_b`.
-pub fn iou_batch(boxes_a: &[Rect], boxes_b: &[Rect]) -> Array2<f32> {
+pub fn iou_batch<B: BoxGeometry>(boxes_a: &[B], boxes_b: &[B]) -> Array2<f32> {
     let mut dists = Array2::zeros((boxes_a.len(), boxes_b.len()));
     for (i, a) in boxes_a.iter().enumerate() {
         for (j, b) in boxes_b.iter().enumerate() {
@@ -174,6 +185,14 @@ mod tests {
         assert!((iou - 25.0 / 175.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_clip() {
+        let rect = Rect::new(-10.0, 20.0, 40.0, 100.0).clip(100.0, 80.0);
+        assert_eq!(rect.to_tlwh(), [0.0, 20.0, 30.0, 60.0]);
+        let outside = Rect::new(150.0, 10.0, 20.0, 20.0).clip(100.0, 80.0);
+        assert_eq!(outside.width, 0.0);
+    }
+
     #[test]
     fn test_iou_no_overlap() {
         let a = Rect::new(0.0, 0.0, 10.0, 10.0);
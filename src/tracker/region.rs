@@ -0,0 +1,115 @@
+//! Region-of-interest and ignore-mask filtering.
+
+use crate::tracker::polygon::{Anchor, Polygon};
+use crate::tracker::rect::Rect;
+
+/// Image regions that restrict where detections are used and tracks start.
+///
+/// Each box is placed at its `anchor` point, which is then tested against
+/// the polygons.
+#[derive(Debug, Clone, Default)]
+pub struct RegionFilter {
+    /// Detections outside this polygon are dropped before association.
+    pub roi: Option<Polygon>,
+    /// Detections inside any of these polygons are dropped, e.g. a TV
+    /// screen or a timestamp overlay.
+    pub ignore: Vec<Polygon>,
+    /// New tracks may not start inside these polygons, but existing tracks
+    /// keep matching there, e.g. bands along the frame border.
+    pub no_birth: Vec<Polygon>,
+    /// Remove tracks whose filtered box leaves the ROI instead of keeping
+    /// them as lost.
+    pub remove_on_exit: bool,
+    /// Point on each box tested against the regions.
+    pub anchor: Anchor,
+}
+
+impl RegionFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only use detections inside `roi`.
+    pub fn with_roi(mut self, roi: Polygon) -> Self {
+        self.roi = Some(roi);
+        self
+    }
+
+    /// Drop detections inside `region`.
+    pub fn with_ignore(mut self, region: Polygon) -> Self {
+        self.ignore.push(region);
+        self
+    }
+
+    /// Forbid new tracks inside `region`.
+    pub fn with_no_birth(mut self, region: Polygon) -> Self {
+        self.no_birth.push(region);
+        self
+    }
+
+    /// Forbid new tracks within `margin` pixels of the border of a
+    /// `width` x `height` frame.
+    pub fn with_border_band(self, width: f32, height: f32, margin: f32) -> Self {
+        let bands = [
+            Rect::new(0.0, 0.0, width, margin),
+            Rect::new(0.0, height - margin, width, margin),
+            Rect::new(0.0, 0.0, margin, height),
+            Rect::new(width - margin, 0.0, margin, height),
+        ];
+        bands.iter().fold(self, |filter, band| {
+            filter.with_no_birth(Polygon::from_rect(band))
+        })
+    }
+
+    /// Remove tracks that leave the ROI.
+    pub fn with_remove_on_exit(mut self, remove: bool) -> Self {
+        self.remove_on_exit = remove;
+        self
+    }
+
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Whether the anchor of `rect` lies inside the ROI, if one is set.
+    pub fn in_roi(&self, rect: &Rect) -> bool {
+        self.roi
+            .as_ref()
+            .is_none_or(|roi| roi.contains(self.anchor.point(rect)))
+    }
+
+    /// Whether a detection with this box should be used at all.
+    pub fn accepts(&self, rect: &Rect) -> bool {
+        let point = self.anchor.point(rect);
+        self.in_roi(rect) && !self.ignore.iter().any(|p| p.contains(point))
+    }
+
+    /// Whether a new track may start with this box.
+    pub fn allows_birth(&self, rect: &Rect) -> bool {
+        let point = self.anchor.point(rect);
+        !self.no_birth.iter().any(|p| p.contains(point))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roi_ignore_and_birth() {
+        let filter = RegionFilter::new()
+            .with_roi(Polygon::from_rect(&Rect::new(0.0, 0.0, 100.0, 100.0)))
+            .with_ignore(Polygon::from_rect(&Rect::new(60.0, 60.0, 20.0, 20.0)))
+            .with_border_band(100.0, 100.0, 10.0)
+            .with_anchor(Anchor::Center);
+
+        assert!(filter.accepts(&Rect::new(20.0, 20.0, 10.0, 10.0)));
+        assert!(!filter.accepts(&Rect::new(120.0, 20.0, 10.0, 10.0)));
+        assert!(!filter.accepts(&Rect::new(65.0, 65.0, 10.0, 10.0)));
+
+        assert!(filter.allows_birth(&Rect::new(20.0, 20.0, 10.0, 10.0)));
+        assert!(!filter.allows_birth(&Rect::new(0.0, 40.0, 10.0, 10.0)));
+        assert!(!filter.allows_birth(&Rect::new(40.0, 92.0, 10.0, 10.0)));
+    }
+}
@@ -0,0 +1,245 @@
+//! Oriented (rotated) bounding boxes.
+
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use ndarray::Array1;
+
+use crate::tracker::geometry::BoxGeometry;
+use crate::tracker::polygon::Polygon;
+use crate::tracker::rect::Rect;
+use crate::tracker::strack::STrack;
+
+/// Weight of the position noise relative to box height.
+const STD_WEIGHT_POSITION: f64 = 1.0 / 20.0;
+/// Weight of the velocity noise relative to box height.
+const STD_WEIGHT_VELOCITY: f64 = 1.0 / 160.0;
+/// Angle noise in radians.
+const STD_ANGLE: f64 = 0.05;
+/// Angular velocity noise in radians per frame.
+const STD_ANGULAR_VELOCITY: f64 = 0.005;
+
+/// Bounding box rotated about its center.
+///
+/// `angle` is in radians, measured from the image x axis towards the y axis
+/// (clockwise on screen, since image y points down), and kept in
+/// `[-pi/2, pi/2)`: a box rotated by half a turn is the same box.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RotatedRect {
+    /// Center x coordinate
+    pub cx: f32,
+    /// Center y coordinate
+    pub cy: f32,
+    /// Extent along the rotated x axis
+    pub width: f32,
+    /// Extent along the rotated y axis
+    pub height: f32,
+    /// Rotation in radians
+    pub angle: f32,
+}
+
+impl RotatedRect {
+    /// Create a rotated box, normalizing `angle` into `[-pi/2, pi/2)`.
+    pub fn new(cx: f32, cy: f32, width: f32, height: f32, angle: f32) -> Self {
+        Self {
+            cx,
+            cy,
+            width,
+            height,
+            angle: wrap_half_turn(angle),
+        }
+    }
+
+    /// Unrotated box with the same extent as `rect`.
+    pub fn from_rect(rect: &Rect) -> Self {
+        let (cx, cy) = rect.center();
+        Self::new(cx, cy, rect.width, rect.height, 0.0)
+    }
+
+    /// Corner points in order around the box.
+    pub fn corners(&self) -> [(f32, f32); 4] {
+        let (sin, cos) = self.angle.sin_cos();
+        let (hw, hh) = (self.width / 2.0, self.height / 2.0);
+        [(-hw, -hh), (hw, -hh), (hw, hh), (-hw, hh)]
+            .map(|(x, y)| (self.cx + x * cos - y * sin, self.cy + x * sin + y * cos))
+    }
+
+    pub fn to_polygon(&self) -> Polygon {
+        Polygon::new(self.corners().to_vec())
+    }
+
+    pub fn area(&self) -> f32 {
+        self.width * self.height
+    }
+
+    /// Intersection over union of the two rotated boxes.
+    pub fn iou(&self, other: &RotatedRect) -> f32 {
+        if self.bounding_rect().iou(&other.bounding_rect()) == 0.0 {
+            return 0.0;
+        }
+        let inter = self.to_polygon().clip_convex(&other.to_polygon()).area();
+        let union = self.area() + other.area() - inter;
+        if union > 0.0 {
+            (inter / union).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Rotated boxes, filtered as center, aspect ratio, height and angle.
+impl BoxGeometry for RotatedRect {
+    const DIM: usize = 5;
+
+    fn to_measurement(&self) -> Vec<f64> {
+        let aspect = if self.height > 0.0 {
+            self.width / self.height
+        } else {
+            0.0
+        };
+        [self.cx, self.cy, aspect, self.height, self.angle]
+            .iter()
+            .map(|&v| v as f64)
+            .collect()
+    }
+
+    fn from_state(state: &Array1<f64>) -> Self {
+        let (aspect, height) = (state[2] as f32, state[3] as f32);
+        RotatedRect::new(
+            state[0] as f32,
+            state[1] as f32,
+            aspect * height,
+            height,
+            state[4] as f32,
+        )
+    }
+
+    fn iou(&self, other: &Self) -> f32 {
+        RotatedRect::iou(self, other)
+    }
+
+    fn bounding_rect(&self) -> Rect {
+        let corners = self.corners();
+        let (mut x1, mut y1) = corners[0];
+        let (mut x2, mut y2) = corners[0];
+        for &(x, y) in &corners[1..] {
+            (x1, y1) = (x1.min(x), y1.min(y));
+            (x2, y2) = (x2.max(x), y2.max(y));
+        }
+        Rect::from_tlbr(x1, y1, x2, y2)
+    }
+
+    fn initial_std(measurement: &[f64]) -> Vec<f64> {
+        let (pos, vel) = (
+            2.0 * STD_WEIGHT_POSITION * measurement[3],
+            10.0 * STD_WEIGHT_VELOCITY * measurement[3],
+        );
+        vec![
+            pos,
+            pos,
+            1e-2,
+            pos,
+            2.0 * STD_ANGLE,
+            vel,
+            vel,
+            1e-5,
+            vel,
+            10.0 * STD_ANGULAR_VELOCITY,
+        ]
+    }
+
+    fn process_std(state: &Array1<f64>) -> Vec<f64> {
+        let (pos, vel) = (
+            STD_WEIGHT_POSITION * state[3],
+            STD_WEIGHT_VELOCITY * state[3],
+        );
+        vec![
+            pos,
+            pos,
+            1e-2,
+            pos,
+            STD_ANGLE,
+            vel,
+            vel,
+            1e-5,
+            vel,
+            STD_ANGULAR_VELOCITY,
+        ]
+    }
+
+    fn measurement_std(state: &Array1<f64>) -> Vec<f64> {
+        let pos = STD_WEIGHT_POSITION * state[3];
+        vec![pos, pos, 1e-1, pos, STD_ANGLE]
+    }
+
+    fn wrap_residual(residual: &mut Array1<f64>) {
+        residual[4] = wrap_half_turn(residual[4] as f32) as f64;
+    }
+
+    fn hold_size(state: &mut Array1<f64>) {
+        state[8] = 0.0;
+    }
+}
+
+impl STrack<RotatedRect> {
+    /// Change in box angle per frame, in radians.
+    pub fn angular_rate(&self) -> Option<f32> {
+        self.mean.as_ref().map(|m| m[9] as f32)
+    }
+}
+
+/// Wrap an angle into `[-pi/2, pi/2)`.
+fn wrap_half_turn(angle: f32) -> f32 {
+    (angle + FRAC_PI_2).rem_euclid(PI) - FRAC_PI_2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_angle_is_normalized() {
+        let a = RotatedRect::new(0.0, 0.0, 4.0, 2.0, PI + 0.1);
+        assert!((a.angle - 0.1).abs() < 1e-5);
+        let b = RotatedRect::new(0.0, 0.0, 4.0, 2.0, FRAC_PI_2);
+        assert!((b.angle + FRAC_PI_2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_rotated_iou() {
+        let a = RotatedRect::new(0.0, 0.0, 10.0, 10.0, 0.0);
+        assert!((a.iou(&a) - 1.0).abs() < 1e-5);
+
+        // Matches the axis-aligned IoU when unrotated.
+        let r1 = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let r2 = Rect::new(5.0, 5.0, 10.0, 10.0);
+        let rotated = RotatedRect::from_rect(&r1).iou(&RotatedRect::from_rect(&r2));
+        assert!((rotated - r1.iou(&r2)).abs() < 1e-5);
+
+        // A square rotated by 45 degrees inside a circumscribing square.
+        let diamond = RotatedRect::new(0.0, 0.0, 10.0, 10.0, PI / 4.0);
+        let outer = RotatedRect::new(0.0, 0.0, 200f32.sqrt(), 200f32.sqrt(), 0.0);
+        assert!((diamond.iou(&outer) - 0.5).abs() < 1e-4);
+
+        // Equal boxes whose angles differ by half a turn.
+        let c = RotatedRect::new(3.0, 4.0, 20.0, 5.0, 1.5);
+        let d = RotatedRect::new(3.0, 4.0, 20.0, 5.0, 1.5 - PI);
+        assert!((c.iou(&d) - 1.0).abs() < 1e-4);
+
+        let e = RotatedRect::new(100.0, 100.0, 10.0, 10.0, 0.3);
+        assert_eq!(a.iou(&e), 0.0);
+    }
+
+    #[test]
+    fn test_bounding_rect() {
+        let r = RotatedRect::new(0.0, 0.0, 10.0, 10.0, PI / 4.0).bounding_rect();
+        let half = 50f32.sqrt();
+        assert!((r.x + half).abs() < 1e-4 && (r.width - 2.0 * half).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_residual_wraps() {
+        let mut residual = Array1::from_vec(vec![0.0, 0.0, 0.0, 0.0, 3.0]);
+        RotatedRect::wrap_residual(&mut residual);
+        assert!((residual[4] - (3.0 - std::f64::consts::PI)).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,368 @@
+//! Sparse, spatially-indexed association for scenes with many objects.
+//!
+//! The dense path builds a full `M x N` IoU cost matrix and solves one
+//! `max(M, N)` square assignment. Most track/detection pairs in a large scene
+//! don't overlap at all, so here only overlapping pairs are found with a
+//! uniform grid, the resulting bipartite graph is split into connected
+//! components, and each component is solved on its own.
+//!
+//! Pairs that don't overlap have cost `1.0` in the dense matrix, the same for
+//! every such pair, so they can never change which overlapping pairs the
+//! dense solver picks. For thresholds below `1.0` both paths therefore yield
+//! the same matches, up to ties between equal-cost assignments.
+
+use std::collections::HashMap;
+
+use ndarray::Array2;
+
+use crate::tracker::assignment::{AssignmentError, AssignmentSolver};
+use crate::tracker::geometry::{Affinity, BoxGeometry};
+use crate::tracker::matching::{self, AssignmentResult, Detection};
+use crate::tracker::rect::Rect;
+
+/// Boxes spanning more grid cells than this are checked against every
+/// track instead of being inserted into the grid.
+const MAX_CELLS_PER_BOX: i64 = 64;
+
+/// A cost matrix that only stores track/detection pairs with overlap.
+///
+/// Missing entries have cost `1.0`, like non-overlapping pairs in the dense
+/// IoU distance matrix.
+#[derive(Debug, Clone, Default)]
+pub struct SparseCostMatrix {
+    /// Number of tracks (rows).
+    pub rows: usize,
+    /// Number of detections (columns).
+    pub cols: usize,
+    /// `(row, col, cost)` entries, sorted by row then column.
+    pub entries: Vec<(usize, usize, f32)>,
+}
+
+impl SparseCostMatrix {
+    /// Fuse detection scores into the costs, like `matching::fuse_score`.
+    pub fn fuse_score<B: BoxGeometry>(&mut self, detections: &[Detection<B>]) {
+        for (_, j, cost) in &mut self.entries {
+            *cost = 1.0 - (1.0 - *cost) * detections[*j].score;
+        }
+    }
+}
+
+/// Uniform grid over a set of boxes.
+struct Grid {
+    cell_size: f32,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    oversized: Vec<usize>,
+}
+
+impl Grid {
+    fn new(boxes: &[Rect], cell_size: f32) -> Self {
+        let mut grid = Self {
+            cell_size,
+            cells: HashMap::new(),
+            oversized: Vec::new(),
+        };
+        for (idx, rect) in boxes.iter().enumerate() {
+            match grid.cell_range(rect) {
+                Some((x0, y0, x1, y1)) => {
+                    for cy in y0..=y1 {
+                        for cx in x0..=x1 {
+                            grid.cells.entry((cx, cy)).or_default().push(idx);
+                        }
+                    }
+                }
+                None => grid.oversized.push(idx),
+            }
+        }
+        grid
+    }
+
+    /// Inclusive range of cells covered by `rect`, or `None` if it covers too
+    /// many cells or has non-finite coordinates.
+    fn cell_range(&self, rect: &Rect) -> Option<(i64, i64, i64, i64)> {
+        let [x1, y1, x2, y2] = rect.to_tlbr();
+        if ![x1, y1, x2, y2].iter().all(|v| v.is_finite()) {
+            return None;
+        }
+        let x0 = (x1 / self.cell_size).floor() as i64;
+        let y0 = (y1 / self.cell_size).floor() as i64;
+        let x1 = (x2 / self.cell_size).floor() as i64;
+        let y1 = (y2 / self.cell_size).floor() as i64;
+        let cells = (x1 - x0 + 1).saturating_mul(y1 - y0 + 1);
+        (cells <= MAX_CELLS_PER_BOX).then_some((x0, y0, x1, y1))
+    }
+}
+
+/// Compute affinity distances only for pairs with non-zero affinity.
+///
+/// Equivalent to `matching::affinity_distance` with every `1.0` entry
+/// dropped. Candidate pairs are found from the boxes' axis-aligned bounding
+/// rects; track rects are grown by `max_distance` when searching for
+/// center-distance candidates.
+pub fn sparse_affinity_distance<B: BoxGeometry>(
+    track_boxes: &[B],
+    det_boxes: &[B],
+    affinity: Affinity,
+) -> SparseCostMatrix {
+    let mut matrix = SparseCostMatrix {
+        rows: track_boxes.len(),
+        cols: det_boxes.len(),
+        entries: Vec::new(),
+    };
+    if track_boxes.is_empty() || det_boxes.is_empty() {
+        return matrix;
+    }
+
+    let margin = affinity.search_margin();
+    let track_rects: Vec<Rect> = track_boxes
+        .iter()
+        .map(|b| {
+            let r = b.bounding_rect();
+            Rect::new(
+                r.x - margin,
+                r.y - margin,
+                r.width + 2.0 * margin,
+                r.height + 2.0 * margin,
+            )
+        })
+        .collect();
+    let det_rects: Vec<Rect> = det_boxes.iter().map(|b| b.bounding_rect()).collect();
+    let grid = Grid::new(&det_rects, cell_size(&track_rects, &det_rects));
+    // Last track each detection was checked against, to skip repeats.
+    let mut seen = vec![usize::MAX; det_boxes.len()];
+    let mut candidates = Vec::new();
+
+    for (i, track) in track_boxes.iter().enumerate() {
+        candidates.clear();
+        match grid.cell_range(&track_rects[i]) {
+            Some((x0, y0, x1, y1)) => {
+                for cy in y0..=y1 {
+                    for cx in x0..=x1 {
+                        if let Some(cell) = grid.cells.get(&(cx, cy)) {
+                            candidates.extend_from_slice(cell);
+                        }
+                    }
+                }
+                candidates.extend_from_slice(&grid.oversized);
+            }
+            None => candidates.extend(0..det_boxes.len()),
+        }
+        candidates.sort_unstable();
+
+        for &j in &candidates {
+            if seen[j] == i {
+                continue;
+            }
+            seen[j] = i;
+            let similarity = track.affinity(&det_boxes[j], affinity);
+            if similarity > 0.0 {
+                matrix.entries.push((i, j, 1.0 - similarity));
+            }
+        }
+    }
+    matrix
+}
+
+/// Grid cell size: the mean box extent, so a typical box covers a few cells.
+fn cell_size(track_boxes: &[Rect], det_boxes: &[Rect]) -> f32 {
+    let (sum, count) = track_boxes
+        .iter()
+        .chain(det_boxes)
+        .map(|r| r.width.max(r.height))
+        .filter(|s| s.is_finite() && *s > 0.0)
+        .fold((0.0f64, 0usize), |(sum, n), s| (sum + s as f64, n + 1));
+    if count == 0 {
+        1.0
+    } else {
+        ((sum / count as f64) as f32).max(1.0)
+    }
+}
+
+/// Solve a sparse assignment by splitting it into connected components.
+///
+/// Produces the same result as `matching::linear_assignment` on the dense
+/// matrix for `thresh < 1.0`, given an exact solver.
+pub fn linear_assignment_sparse<S: AssignmentSolver + ?Sized>(
+    cost_matrix: &SparseCostMatrix,
+    thresh: f32,
+    solver: &S,
+) -> Result<AssignmentResult, AssignmentError> {
+    let SparseCostMatrix {
+        rows,
+        cols,
+        ref entries,
+    } = *cost_matrix;
+
+    if let Some(&(row, col, value)) = entries.iter().find(|e| !e.2.is_finite()) {
+        return Err(AssignmentError::InvalidCost { row, col, value });
+    }
+
+    // Union-find over rows `0..rows` and columns `rows..rows + cols`.
+    let mut parent: Vec<usize> = (0..rows + cols).collect();
+    fn find(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+    for &(i, j, _) in entries {
+        let a = find(&mut parent, i);
+        let b = find(&mut parent, rows + j);
+        if a != b {
+            parent[a] = b;
+        }
+    }
+
+    // Group rows, columns and entries by component root.
+    let mut components: HashMap<usize, Vec<(usize, usize, f32)>> = HashMap::new();
+    for &(i, j, cost) in entries {
+        let root = find(&mut parent, i);
+        components.entry(root).or_default().push((i, j, cost));
+    }
+
+    let mut matches = Vec::new();
+    let mut track_matched = vec![false; rows];
+    let mut det_matched = vec![false; cols];
+
+    for component in components.into_values() {
+        if let [(i, j, cost)] = component[..] {
+            if cost <= thresh {
+                matches.push((i, j));
+                track_matched[i] = true;
+                det_matched[j] = true;
+            }
+            continue;
+        }
+
+        let mut row_ids: Vec<usize> = component.iter().map(|e| e.0).collect();
+        let mut col_ids: Vec<usize> = component.iter().map(|e| e.1).collect();
+        row_ids.sort_unstable();
+        row_ids.dedup();
+        col_ids.sort_unstable();
+        col_ids.dedup();
+
+        let mut sub = Array2::from_elem((row_ids.len(), col_ids.len()), 1.0);
+        for &(i, j, cost) in &component {
+            let r = row_ids.binary_search(&i).unwrap();
+            let c = col_ids.binary_search(&j).unwrap();
+            sub[[r, c]] = cost;
+        }
+
+        for (r, c) in matching::linear_assignment(&sub, thresh, solver)?.matches {
+            let (i, j) = (row_ids[r], col_ids[c]);
+            matches.push((i, j));
+            track_matched[i] = true;
+            det_matched[j] = true;
+        }
+    }
+
+    matches.sort_unstable();
+    Ok(AssignmentResult {
+        matches,
+        unmatched_tracks: (0..rows).filter(|&i| !track_matched[i]).collect(),
+        unmatched_detections: (0..cols).filter(|&j| !det_matched[j]).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::assignment::LapjvSolver;
+
+    fn to_dense(matrix: &SparseCostMatrix) -> Array2<f32> {
+        let mut dense = Array2::from_elem((matrix.rows, matrix.cols), 1.0);
+        for &(i, j, cost) in &matrix.entries {
+            dense[[i, j]] = cost;
+        }
+        dense
+    }
+
+    /// Deterministic pseudo-random boxes in a crowded scene.
+    fn random_boxes(seed: u64, count: usize, jitter: f32) -> Vec<Rect> {
+        let mut state = seed;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as f32 / (1u64 << 31) as f32
+        };
+        (0..count)
+            .map(|k| {
+                let x = (k % 25) as f32 * 40.0 + next() * jitter;
+                let y = (k / 25) as f32 * 60.0 + next() * jitter;
+                Rect::new(x, y, 30.0 + next() * 20.0, 50.0 + next() * 20.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sparse_iou_matches_dense() {
+        let tracks = random_boxes(1, 300, 30.0);
+        let dets = random_boxes(2, 280, 30.0);
+
+        let sparse = sparse_affinity_distance(&tracks, &dets, Affinity::Iou);
+        let dense = matching::affinity_distance(&tracks, &dets, Affinity::Iou);
+        assert_eq!(to_dense(&sparse), dense);
+        assert!(sparse.entries.len() < tracks.len() * dets.len() / 10);
+    }
+
+    #[test]
+    fn test_sparse_center_distance_matches_dense() {
+        let tracks = random_boxes(3, 200, 30.0);
+        let dets = random_boxes(4, 210, 30.0);
+        let affinity = Affinity::CenterDistance { max_distance: 80.0 };
+
+        let sparse = sparse_affinity_distance(&tracks, &dets, affinity);
+        let dense = matching::affinity_distance(&tracks, &dets, affinity);
+        assert_eq!(to_dense(&sparse), dense);
+    }
+
+    #[test]
+    fn test_sparse_assignment_matches_dense() {
+        for seed in 0..5 {
+            let tracks = random_boxes(seed * 2, 250, 25.0);
+            let dets = random_boxes(seed * 2 + 1, 260, 25.0);
+            let scores: Vec<Detection> = dets
+                .iter()
+                .enumerate()
+                .map(|(k, r)| Detection::from_rect(*r, 0.5 + (k % 7) as f32 * 0.07))
+                .collect();
+
+            let mut sparse = sparse_affinity_distance(&tracks, &dets, Affinity::Iou);
+            sparse.fuse_score(&scores);
+            let mut dense = matching::affinity_distance(&tracks, &dets, Affinity::Iou);
+            matching::fuse_score(&mut dense, &scores);
+
+            let a = linear_assignment_sparse(&sparse, 0.8, &LapjvSolver).unwrap();
+            let b = matching::linear_assignment(&dense, 0.8, &LapjvSolver).unwrap();
+            assert_eq!(a.matches, b.matches);
+            assert_eq!(a.unmatched_tracks, b.unmatched_tracks);
+            assert_eq!(a.unmatched_detections, b.unmatched_detections);
+        }
+    }
+
+    #[test]
+    fn test_sparse_handles_empty_and_oversized() {
+        let result = linear_assignment_sparse(
+            &sparse_affinity_distance(&[], &[Rect::default()], Affinity::Iou),
+            0.8,
+            &LapjvSolver,
+        )
+        .unwrap();
+        assert_eq!(result.unmatched_detections, vec![0]);
+
+        let tracks = vec![
+            Rect::new(0.0, 0.0, 10.0, 10.0),
+            Rect::new(500.0, 500.0, 10.0, 10.0),
+        ];
+        let dets = vec![
+            Rect::new(0.0, 0.0, 1000.0, 1000.0),
+            Rect::new(1.0, 1.0, 10.0, 10.0),
+        ];
+        let sparse = sparse_affinity_distance(&tracks, &dets, Affinity::Iou);
+        assert_eq!(
+            to_dense(&sparse),
+            matching::affinity_distance(&tracks, &dets, Affinity::Iou)
+        );
+    }
+}
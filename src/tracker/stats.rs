@@ -0,0 +1,116 @@
+//! Cumulative timing and counters for `BYTETracker`.
+
+use std::time::{Duration, Instant};
+
+/// Timings and counters accumulated by a `BYTETracker` since it was created
+/// or since the last `reset_stats` call.
+///
+/// Timings are wall-clock durations summed over all frames. The `tracked`,
+/// `lost` and `removed` fields are list sizes after the most recent frame.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackerStats {
+    /// Frames processed with `update`.
+    pub frames: u64,
+    /// Frames processed with `predict` only.
+    pub predicted_frames: u64,
+    /// Time spent propagating tracks with the Kalman filter.
+    pub prediction_time: Duration,
+    /// Time spent building IoU cost matrices and fusing scores.
+    pub iou_time: Duration,
+    /// Time spent solving linear assignments.
+    pub assignment_time: Duration,
+    /// Time spent removing duplicate tracked/lost tracks.
+    pub duplicate_removal_time: Duration,
+    /// Total time spent in `update` and `predict`.
+    pub total_time: Duration,
+    /// Detections at or above `track_thresh`.
+    pub high_detections: u64,
+    /// Detections below `track_thresh` kept for the second association.
+    pub low_detections: u64,
+    /// Detections dropped by the region filter.
+    pub filtered_detections: u64,
+    /// Matches in the first association (high-score detections).
+    pub first_matches: u64,
+    /// Matches in the second association (low-score detections).
+    pub second_matches: u64,
+    /// Matches between unconfirmed tracks and leftover detections.
+    pub unconfirmed_matches: u64,
+    /// New tracks started.
+    pub tracks_started: u64,
+    /// Tracks marked lost.
+    pub tracks_lost: u64,
+    /// Tracks removed.
+    pub tracks_removed: u64,
+    /// Frames where the assignment solver failed.
+    pub assignment_errors: u64,
+    /// Size of the tracked list after the last frame.
+    pub tracked: usize,
+    /// Size of the lost list after the last frame.
+    pub lost: usize,
+    /// Size of the removed list after the last frame.
+    pub removed: usize,
+}
+
+impl TrackerStats {
+    /// Add another tracker's timings, counters and list sizes to these.
+    pub fn merge(&mut self, other: &TrackerStats) {
+        self.frames += other.frames;
+        self.predicted_frames += other.predicted_frames;
+        self.prediction_time += other.prediction_time;
+        self.iou_time += other.iou_time;
+        self.assignment_time += other.assignment_time;
+        self.duplicate_removal_time += other.duplicate_removal_time;
+        self.total_time += other.total_time;
+        self.high_detections += other.high_detections;
+        self.low_detections += other.low_detections;
+        self.filtered_detections += other.filtered_detections;
+        self.first_matches += other.first_matches;
+        self.second_matches += other.second_matches;
+        self.unconfirmed_matches += other.unconfirmed_matches;
+        self.tracks_started += other.tracks_started;
+        self.tracks_lost += other.tracks_lost;
+        self.tracks_removed += other.tracks_removed;
+        self.assignment_errors += other.assignment_errors;
+        self.tracked += other.tracked;
+        self.lost += other.lost;
+        self.removed += other.removed;
+    }
+
+    /// Mean time per processed frame, or zero if no frames were processed.
+    pub fn mean_frame_time(&self) -> Duration {
+        let frames = self.frames + self.predicted_frames;
+        if frames == 0 {
+            Duration::ZERO
+        } else {
+            self.total_time.div_f64(frames as f64)
+        }
+    }
+}
+
+/// Run `f` and add its wall-clock duration to `total`.
+pub(crate) fn timed<T>(total: &mut Duration, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let out = f();
+    *total += start.elapsed();
+    out
+}
+
+/// Enter a debug-level tracing span until the end of the enclosing block.
+/// Expands to nothing without the `tracing` feature.
+macro_rules! stage_span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($($arg)*).entered();
+    };
+}
+
+/// Emit a debug-level tracing event. Expands to nothing without the
+/// `tracing` feature.
+macro_rules! stage_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+pub(crate) use {stage_event, stage_span};
@@ -4,7 +4,14 @@ use std::sync::atomic::{AtomicU64, Ordering};
 
 use ndarray::{Array1, Array2};
 
+use crate::tracker::geometry::BoxGeometry;
+use crate::tracker::ground::GroundKalmanFilter;
+use crate::tracker::history::{TrajectoryHistory, TrajectoryPoint};
 use crate::tracker::kalman_filter::KalmanFilter;
+use crate::tracker::keypoints::Keypoint;
+use crate::tracker::mask::RleMask;
+use crate::tracker::matching::Detection;
+use crate::tracker::motion::{Forecast, UncertaintyEllipse, Velocity};
 use crate::tracker::rect::Rect;
 use crate::tracker::track_state::TrackState;
 
@@ -21,9 +28,9 @@ fn next_track_id() -> u64 {
     TRACK_ID_COUNTER.fetch_add(1, Ordering::SeqCst) + 1
 }
 
-/// Single object track.
+/// Single object track, following boxes of geometry `B`.
 #[derive(Debug, Clone)]
-pub struct STrack {
+pub struct STrack<B: BoxGeometry = Rect> {
     /// Unique track identifier
     pub track_id: u64,
     /// Current track state
@@ -38,17 +45,34 @@ pub struct STrack {
     pub start_frame: u32,
     /// Number of frames since track was last seen
     pub tracklet_len: u32,
-    /// Kalman filter state mean (8-dim)
+    /// Kalman filter state mean (`2 * B::DIM`, 8-dim for `Rect`)
     pub mean: Option<Array1<f64>>,
-    /// Kalman filter state covariance (8x8)
+    /// Frame the Kalman mean has been predicted to
+    pub mean_frame: u32,
+    /// Kalman filter state covariance (`2 * B::DIM` square, 8x8 for `Rect`)
     pub covariance: Option<Array2<f64>>,
-    /// Original detection bounding box (TLWH format)
-    pub tlwh: Rect,
+    /// Original detection bounding box (TLWH format for `Rect`)
+    pub tlwh: B,
+    /// Ground-plane filter state mean `[x, y, vx, vy]`, in ground-plane mode
+    pub ground_mean: Option<Array1<f64>>,
+    /// Ground-plane filter state covariance (4x4), in ground-plane mode
+    pub ground_covariance: Option<Array2<f64>>,
+    /// Recent observations, if trajectory history is enabled
+    pub history: Option<TrajectoryHistory<B>>,
+    /// Image size `(width, height)` the track was output for, if known
+    pub frame_size: Option<(u32, u32)>,
+    /// Segmentation mask of the last matched detection, if it had one
+    pub mask: Option<RleMask>,
+    /// Keypoints of the last matched detection, if it had any
+    pub keypoints: Option<Vec<Keypoint>>,
+    /// Keypoints smoothed over matched detections, when the tracker has a
+    /// keypoint config
+    pub smoothed_keypoints: Option<Vec<Keypoint>>,
 }
 
-impl STrack {
+impl<B: BoxGeometry> STrack<B> {
     /// Create a new STrack from a detection.
-    pub fn new(tlwh: Rect, score: f32) -> Self {
+    pub fn new(tlwh: B, score: f32) -> Self {
         Self {
             track_id: 0,
             state: TrackState::New,
@@ -58,44 +82,98 @@ impl STrack {
             start_frame: 0,
             tracklet_len: 0,
             mean: None,
+            mean_frame: 0,
             covariance: None,
             tlwh,
+            ground_mean: None,
+            ground_covariance: None,
+            history: None,
+            frame_size: None,
+            mask: None,
+            keypoints: None,
+            smoothed_keypoints: None,
+        }
+    }
+
+    /// Create a new STrack from a detection, keeping its mask and keypoints.
+    pub fn from_detection(detection: Detection<B>) -> Self {
+        Self {
+            mask: detection.mask,
+            keypoints: detection.keypoints,
+            ..Self::new(detection.bbox, detection.score)
         }
     }
 
-    /// Get the current bounding box in TLWH format.
-    pub fn tlwh(&self) -> Rect {
+    /// Keep the last `capacity` observations of this track.
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        self.history = Some(TrajectoryHistory::new(capacity));
+        self
+    }
+
+    /// Recorded observations from oldest to newest; empty if history is
+    /// disabled.
+    pub fn trajectory(&self) -> impl DoubleEndedIterator<Item = &TrajectoryPoint<B>> {
+        self.history.iter().flat_map(|h| h.iter())
+    }
+
+    /// Append the current filtered state and an observed box to the history.
+    fn record(&mut self, observed: B) {
+        let filtered = self.tlwh();
+        let (frame_id, score) = (self.frame_id, self.score);
+        if let Some(history) = &mut self.history {
+            history.push(TrajectoryPoint {
+                frame_id,
+                observed,
+                filtered,
+                score,
+            });
+        }
+    }
+
+    /// Get the current bounding box (in TLWH format for `Rect`).
+    pub fn tlwh(&self) -> B {
         match &self.mean {
-            Some(mean) => {
-                let cx = mean[0] as f32;
-                let cy = mean[1] as f32;
-                let aspect = mean[2] as f32;
-                let h = mean[3] as f32;
-                Rect::from_xyah(cx, cy, aspect, h)
-            }
+            Some(mean) => B::from_state(mean),
             None => self.tlwh,
         }
     }
 
+    /// Box to report for this track.
+    ///
+    /// Tracks returned by `BYTETracker::update_in_frame` carry the frame
+    /// size, and for them this is the box clipped to the image. Otherwise
+    /// it is the unclipped `rect`. `tlwh` always stays unclipped, since the
+    /// motion model needs the full box of a partly visible object.
+    pub fn output_tlwh(&self) -> Rect {
+        match self.frame_size {
+            Some((width, height)) => self.rect().clip(width as f32, height as f32),
+            None => self.rect(),
+        }
+    }
+
+    /// Whether the box center, extrapolated at the current velocity to
+    /// `frame_id`, lies inside a `width` x `height` image.
+    pub fn predicted_in_frame(&self, frame_id: u32, width: u32, height: u32) -> bool {
+        let steps = frame_id.saturating_sub(self.mean_frame);
+        let (cx, cy) = self.rect().center();
+        let (vx, vy) = self.velocity().map_or((0.0, 0.0), |v| (v.vx, v.vy));
+        let (x, y) = (cx + vx * steps as f32, cy + vy * steps as f32);
+        (0.0..width as f32).contains(&x) && (0.0..height as f32).contains(&y)
+    }
+
+    /// Axis-aligned box enclosing the current box; the box itself for `Rect`.
     pub fn rect(&self) -> Rect {
-        self.tlwh()
+        self.tlwh().bounding_rect()
     }
 
     pub fn end_frame(&self) -> u32 {
         self.frame_id
     }
 
-    pub fn activate(&mut self, kalman_filter: &KalmanFilter, frame_id: u32) {
+    pub fn activate(&mut self, kalman_filter: &KalmanFilter<B>, frame_id: u32) {
         self.track_id = next_track_id();
 
-        let xyah = self.tlwh.to_xyah();
-        let xyah_f64 = [
-            xyah[0] as f64,
-            xyah[1] as f64,
-            xyah[2] as f64,
-            xyah[3] as f64,
-        ];
-        let (mean, covariance) = kalman_filter.initiate(xyah_f64);
+        let (mean, covariance) = kalman_filter.initiate(&self.tlwh);
 
         self.mean = Some(mean);
         self.covariance = Some(covariance);
@@ -107,26 +185,20 @@ impl STrack {
         }
 
         self.frame_id = frame_id;
+        self.mean_frame = frame_id;
         self.start_frame = frame_id;
+        self.record(self.tlwh);
     }
 
     pub fn re_activate(
         &mut self,
-        new_track: &STrack,
-        kalman_filter: &KalmanFilter,
+        new_track: &STrack<B>,
+        kalman_filter: &KalmanFilter<B>,
         frame_id: u32,
         new_id: bool,
     ) {
-        let xyah = new_track.tlwh.to_xyah();
-        let xyah_f64 = [
-            xyah[0] as f64,
-            xyah[1] as f64,
-            xyah[2] as f64,
-            xyah[3] as f64,
-        ];
-
         if let (Some(mean), Some(cov)) = (&self.mean, &self.covariance) {
-            let (new_mean, new_cov) = kalman_filter.update(mean, cov, xyah_f64);
+            let (new_mean, new_cov) = kalman_filter.update(mean, cov, &new_track.tlwh);
             self.mean = Some(new_mean);
             self.covariance = Some(new_cov);
         }
@@ -135,27 +207,33 @@ impl STrack {
         self.state = TrackState::Tracked;
         self.is_activated = true;
         self.frame_id = frame_id;
+        self.mean_frame = frame_id;
         self.score = new_track.score;
 
         if new_id {
             self.track_id = next_track_id();
         }
+        if new_track.mask.is_some() {
+            self.mask = new_track.mask.clone();
+        }
+        if new_track.keypoints.is_some() {
+            self.keypoints = new_track.keypoints.clone();
+        }
+        self.record(new_track.tlwh);
     }
 
-    pub fn update(&mut self, new_track: &STrack, kalman_filter: &KalmanFilter, frame_id: u32) {
+    pub fn update(
+        &mut self,
+        new_track: &STrack<B>,
+        kalman_filter: &KalmanFilter<B>,
+        frame_id: u32,
+    ) {
         self.frame_id = frame_id;
+        self.mean_frame = frame_id;
         self.tracklet_len += 1;
 
-        let xyah = new_track.tlwh.to_xyah();
-        let xyah_f64 = [
-            xyah[0] as f64,
-            xyah[1] as f64,
-            xyah[2] as f64,
-            xyah[3] as f64,
-        ];
-
         if let (Some(mean), Some(cov)) = (&self.mean, &self.covariance) {
-            let (new_mean, new_cov) = kalman_filter.update(mean, cov, xyah_f64);
+            let (new_mean, new_cov) = kalman_filter.update(mean, cov, &new_track.tlwh);
             self.mean = Some(new_mean);
             self.covariance = Some(new_cov);
         }
@@ -163,20 +241,136 @@ impl STrack {
         self.state = TrackState::Tracked;
         self.is_activated = true;
         self.score = new_track.score;
+        if new_track.mask.is_some() {
+            self.mask = new_track.mask.clone();
+        }
+        if new_track.keypoints.is_some() {
+            self.keypoints = new_track.keypoints.clone();
+        }
+        self.record(new_track.tlwh);
     }
 
-    pub fn predict(&mut self, kalman_filter: &KalmanFilter) {
+    pub fn predict(&mut self, kalman_filter: &KalmanFilter<B>) {
         if let (Some(mean), Some(cov)) = (&self.mean, &self.covariance) {
             let mut mean_to_predict = mean.clone();
             if self.state != TrackState::Tracked {
-                mean_to_predict[7] = 0.0;
+                B::hold_size(&mut mean_to_predict);
             }
             let (new_mean, new_cov) = kalman_filter.predict(&mean_to_predict, cov);
             self.mean = Some(new_mean);
             self.covariance = Some(new_cov);
+            self.mean_frame += 1;
         }
     }
 
+    /// Center velocity per frame, from the Kalman state.
+    ///
+    /// Units are the box's coordinate units: pixels for 2D boxes, and the
+    /// ground-plane `x`/`y` units (e.g. metres) for `Box3D`.
+    pub fn velocity(&self) -> Option<Velocity> {
+        self.mean
+            .as_ref()
+            .map(|m| Velocity::new(m[B::DIM] as f32, m[B::DIM + 1] as f32))
+    }
+
+    /// Center velocity per second at the given frame rate, in the box's
+    /// coordinate units.
+    pub fn velocity_per_second(&self, frame_rate: f32) -> Option<Velocity> {
+        self.velocity().map(|v| v.scaled(frame_rate))
+    }
+
+    /// Center speed per frame, in the box's coordinate units.
+    pub fn speed(&self) -> Option<f32> {
+        self.velocity().map(|v| v.speed())
+    }
+
+    /// Direction of motion in radians; see `Velocity::heading`.
+    pub fn heading(&self) -> Option<f32> {
+        self.velocity().map(|v| v.heading())
+    }
+
+    /// `n_sigma` uncertainty ellipse of the box center, in the box's
+    /// coordinate units.
+    pub fn position_uncertainty(&self, n_sigma: f32) -> Option<UncertaintyEllipse> {
+        let (mean, cov) = (self.mean.as_ref()?, self.covariance.as_ref()?);
+        Some(UncertaintyEllipse::from_covariance(
+            (mean[0] as f32, mean[1] as f32),
+            cov[[0, 0]],
+            cov[[0, 1]],
+            cov[[1, 1]],
+            n_sigma,
+        ))
+    }
+
+    /// `n_sigma` uncertainty ellipse of the center velocity per frame, in the
+    /// box's coordinate units, centered on the estimated velocity.
+    pub fn velocity_uncertainty(&self, n_sigma: f32) -> Option<UncertaintyEllipse> {
+        let (mean, cov) = (self.mean.as_ref()?, self.covariance.as_ref()?);
+        let (vx, vy) = (B::DIM, B::DIM + 1);
+        Some(UncertaintyEllipse::from_covariance(
+            (mean[vx] as f32, mean[vy] as f32),
+            cov[[vx, vx]],
+            cov[[vx, vy]],
+            cov[[vy, vy]],
+            n_sigma,
+        ))
+    }
+
+    /// Roll the motion model forward `steps` frames without changing the track.
+    ///
+    /// # Returns
+    /// One forecast per step, `1..=steps` frames ahead, with growing
+    /// covariance. Empty if the track has no motion state yet.
+    pub fn forecast(&self, steps: u32) -> Vec<Forecast<B>> {
+        if self.mean.is_none() {
+            return Vec::new();
+        }
+        let kalman_filter = KalmanFilter::default();
+        let mut track = self.clone();
+        (1..=steps)
+            .map(|steps_ahead| {
+                track.predict(&kalman_filter);
+                Forecast {
+                    steps_ahead,
+                    rect: track.tlwh(),
+                    mean: track.mean.clone().expect("mean checked above"),
+                    covariance: track.covariance.clone().expect("covariance set with mean"),
+                }
+            })
+            .collect()
+    }
+
+    /// Filtered ground-plane position, in ground-plane mode.
+    pub fn ground_position(&self) -> Option<(f64, f64)> {
+        self.ground_mean.as_ref().map(|m| (m[0], m[1]))
+    }
+
+    /// Filtered ground-plane velocity in world units per second, in
+    /// ground-plane mode.
+    pub fn ground_velocity(&self) -> Option<(f64, f64)> {
+        self.ground_mean.as_ref().map(|m| (m[2], m[3]))
+    }
+
+    /// Propagate the ground-plane filter by one frame.
+    pub fn ground_predict(&mut self, filter: &GroundKalmanFilter) {
+        if let (Some(mean), Some(cov)) = (&self.ground_mean, &self.ground_covariance) {
+            let (new_mean, new_cov) = filter.predict(mean, cov);
+            self.ground_mean = Some(new_mean);
+            self.ground_covariance = Some(new_cov);
+        }
+    }
+
+    /// Correct the ground-plane filter with a measured foot point,
+    /// initializing it on the first measurement.
+    pub fn ground_update(&mut self, filter: &GroundKalmanFilter, point: (f64, f64)) {
+        let (new_mean, new_cov) = match (&self.ground_mean, &self.ground_covariance) {
+            (Some(mean), Some(cov)) => filter.update(mean, cov, point),
+            _ => filter.initiate(point),
+        };
+        self.ground_mean = Some(new_mean);
+        self.ground_covariance = Some(new_cov);
+    }
+
     pub fn mark_lost(&mut self) {
         self.state = TrackState::Lost;
     }
@@ -185,9 +379,25 @@ impl STrack {
         self.state = TrackState::Removed;
     }
 
-    pub fn multi_predict(stracks: &mut [STrack], kalman_filter: &KalmanFilter) {
+    pub fn multi_predict(stracks: &mut [STrack<B>], kalman_filter: &KalmanFilter<B>) {
         for strack in stracks.iter_mut() {
             strack.predict(kalman_filter);
         }
     }
 }
+
+impl STrack {
+    /// Relative change in box height per frame, e.g. `0.01` for a box
+    /// growing by 1% each frame as the object approaches.
+    pub fn scale_rate(&self) -> Option<f32> {
+        self.mean
+            .as_ref()
+            .filter(|m| m[3] > 0.0)
+            .map(|m| (m[7] / m[3]) as f32)
+    }
+
+    /// Change in aspect ratio (width / height) per frame.
+    pub fn aspect_rate(&self) -> Option<f32> {
+        self.mean.as_ref().map(|m| m[6] as f32)
+    }
+}
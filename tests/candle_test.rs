@@ -0,0 +1,51 @@
+#![cfg(feature = "candle-backend")]
+
+//! Tests for the candle backend using a model that reports one box per image,
+//! scored by the mean input intensity.
+
+use bytetrack_rs::{CandleDetector, CandleModel, DetectionSource, ImageView, RawDetection};
+use candle_core::{Device, Tensor};
+
+struct MeanModel;
+
+impl CandleModel for MeanModel {
+    fn forward(&self, input: &Tensor) -> candle_core::Result<Vec<RawDetection>> {
+        let score = input.mean_all()?.to_scalar::<f32>()?;
+        Ok(vec![RawDetection {
+            bbox: [4.0, 4.0, 4.0, 4.0],
+            score,
+            class_id: Some(0),
+        }])
+    }
+
+    fn input_size(&self) -> (u32, u32, u32) {
+        (3, 8, 8)
+    }
+}
+
+#[test]
+fn test_candle_detect_letterboxed_frame() {
+    let mut detector = CandleDetector::new(MeanModel, Device::Cpu);
+
+    let frame = vec![255u8; 16 * 16 * 3];
+    let dets = detector.detect(&frame, 16, 16).unwrap();
+
+    assert_eq!(dets.len(), 1);
+    assert!((dets[0].score - 1.0).abs() < 1e-5);
+    assert_eq!(dets[0].bbox.to_tlbr(), [4.0, 4.0, 12.0, 12.0]);
+}
+
+#[test]
+fn test_candle_detect_batch() {
+    let mut detector = CandleDetector::new(MeanModel, Device::Cpu).with_conf_threshold(0.5);
+
+    let bright = vec![255u8; 8 * 8 * 3];
+    let dark = vec![0u8; 8 * 8 * 3];
+    let dets = detector
+        .detect_batch(&[ImageView::rgb(&bright, 8, 8), ImageView::rgb(&dark, 8, 8)])
+        .unwrap();
+
+    assert_eq!(dets.len(), 2);
+    assert_eq!(dets[0].len(), 1);
+    assert!(dets[1].is_empty());
+}
@@ -1,5 +1,35 @@
 use bytetrack_rs::tracker::reset_track_id_counter;
-use bytetrack_rs::{BYTETracker, Detection, TrackerConfig};
+use bytetrack_rs::tracker::{
+    AuctionSolver, BoxGeometry, GroundCalibration, GroundPlaneConfig, Homography,
+};
+use bytetrack_rs::{
+    AssignmentError, AssignmentMethod, BYTETracker, Detection, STrack, TrackerConfig,
+};
+
+/// Feed `frames` of detections to a tracker built from `config` and report
+/// whether the track picked out by `target` keeps one ID on every frame.
+///
+/// The detection order is reversed on odd frames so that objects with equal
+/// box costs can't keep their identities by accident.
+fn keeps_identity<B: BoxGeometry>(
+    config: TrackerConfig,
+    frames: impl IntoIterator<Item = Vec<Detection<B>>>,
+    target: impl Fn(usize, &STrack<B>) -> bool,
+) -> bool {
+    let mut tracker = BYTETracker::<B>::new(config);
+    let mut id = None;
+    for (frame, mut dets) in frames.into_iter().enumerate() {
+        if frame % 2 == 1 {
+            dets.reverse();
+        }
+        let tracks = tracker.update(dets);
+        match tracks.iter().find(|t| target(frame, t)) {
+            Some(t) if *id.get_or_insert(t.track_id) == t.track_id => {}
+            _ => return false,
+        }
+    }
+    true
+}
 
 #[test]
 fn test_basic_tracking() {
@@ -43,3 +73,565 @@ fn test_basic_tracking() {
     assert_eq!(tracks5.len(), 1);
     assert_eq!(tracks5[0].track_id, id1);
 }
+
+#[test]
+fn test_tracker_stats() {
+    let mut tracker = BYTETracker::new(TrackerConfig::default());
+
+    tracker.update(vec![Detection::new(100.0, 100.0, 200.0, 200.0, 0.9)]);
+    tracker.update(vec![Detection::new(105.0, 105.0, 205.0, 205.0, 0.9)]);
+    tracker.update(vec![Detection::new(110.0, 110.0, 210.0, 210.0, 0.2)]);
+    tracker.update(vec![]);
+    tracker.predict();
+
+    let stats = tracker.stats();
+    assert_eq!(stats.frames, 4);
+    assert_eq!(stats.predicted_frames, 1);
+    assert_eq!(stats.high_detections, 2);
+    assert_eq!(stats.low_detections, 1);
+    assert_eq!(stats.first_matches, 1);
+    assert_eq!(stats.second_matches, 1);
+    assert_eq!(stats.tracks_started, 1);
+    assert_eq!(stats.tracks_lost, 1);
+    assert_eq!((stats.tracked, stats.lost), (0, 1));
+    assert!(stats.total_time >= stats.assignment_time);
+
+    tracker.reset_stats();
+    let stats = tracker.stats();
+    assert_eq!(stats.frames, 0);
+    assert_eq!(stats.lost, 1);
+}
+
+#[test]
+fn test_sparse_association_matches_dense() {
+    let config = TrackerConfig::default();
+    let mut dense = BYTETracker::new(config.clone());
+    let mut sparse = BYTETracker::new(TrackerConfig {
+        sparse_association: true,
+        ..config
+    });
+
+    for frame in 0..10 {
+        let dets: Vec<Detection> = (0..200)
+            .filter(|k| (k + frame) % 9 != 0)
+            .map(|k| {
+                let x = (k % 20) as f32 * 50.0 + frame as f32 * 2.0;
+                let y = (k / 20) as f32 * 80.0 + (k % 3) as f32 * frame as f32;
+                let score = if k % 5 == 0 { 0.3 } else { 0.9 };
+                Detection::new(x, y, x + 40.0, y + 70.0, score)
+            })
+            .collect();
+
+        // Track IDs come from a global counter, so compare boxes instead.
+        let boxes = |tracks: Vec<STrack>| -> Vec<[f32; 4]> {
+            tracks.iter().map(|t| t.tlwh().to_tlwh()).collect()
+        };
+        let a = boxes(dense.update(dets.clone()));
+        let b = boxes(sparse.update(dets));
+        assert_eq!(a, b);
+        assert!(!a.is_empty());
+    }
+}
+
+#[test]
+fn test_assignment_methods() {
+    let frames: Vec<Vec<Detection>> = (0..5)
+        .map(|f| {
+            let dx = f as f32 * 3.0;
+            vec![
+                Detection::new(10.0 + dx, 10.0, 60.0 + dx, 110.0, 0.9),
+                Detection::new(40.0 + dx, 20.0, 90.0 + dx, 120.0, 0.8),
+                Detection::new(300.0 - dx, 50.0, 350.0 - dx, 150.0, 0.3),
+            ]
+        })
+        .collect();
+
+    let run = |assignment: AssignmentMethod| -> Vec<Vec<[f32; 4]>> {
+        let mut tracker = BYTETracker::new(TrackerConfig {
+            assignment,
+            ..TrackerConfig::default()
+        });
+        frames
+            .iter()
+            .map(|dets| {
+                let tracks = tracker.try_update(dets.clone()).unwrap();
+                tracks.iter().map(|t| t.tlwh().to_tlwh()).collect()
+            })
+            .collect()
+    };
+
+    let expected = run(AssignmentMethod::Lapjv);
+    assert_eq!(run(AssignmentMethod::Hungarian), expected);
+    assert_eq!(run(AssignmentMethod::Greedy), expected);
+    assert_eq!(
+        run(AssignmentMethod::Auction(AuctionSolver::default())),
+        expected
+    );
+}
+
+#[test]
+fn test_try_update_surfaces_errors() {
+    let mut tracker = BYTETracker::new(TrackerConfig::default());
+    tracker.update(vec![Detection::new(10.0, 10.0, 60.0, 110.0, 0.9)]);
+
+    // An infinite score makes the fused cost non-finite.
+    let err = tracker
+        .try_update(vec![Detection::new(10.0, 10.0, 60.0, 110.0, f32::INFINITY)])
+        .unwrap_err();
+    assert!(matches!(err, AssignmentError::InvalidCost { .. }));
+    assert_eq!(tracker.frame_id(), 1);
+    assert_eq!(tracker.stats().assignment_errors, 1);
+    assert_eq!(tracker.stats().high_detections, 1);
+
+    // `update` coasts on prediction instead of dropping tracks, and the
+    // frame only counts as predicted.
+    let tracks = tracker.update(vec![Detection::new(10.0, 10.0, 60.0, 110.0, f32::INFINITY)]);
+    assert_eq!(tracks.len(), 1);
+    assert_eq!(tracker.frame_id(), 2);
+    let stats = tracker.stats();
+    assert_eq!((stats.frames, stats.predicted_frames), (1, 1));
+    assert_eq!(stats.high_detections, 1);
+    assert_eq!(stats.assignment_errors, 2);
+}
+
+#[test]
+fn test_ground_plane_mode() {
+    // 10 pixels per metre.
+    let calibration = GroundCalibration::new(Homography::new([
+        [0.1, 0.0, 0.0],
+        [0.0, 0.1, 0.0],
+        [0.0, 0.0, 1.0],
+    ]))
+    .unwrap();
+    let config = TrackerConfig {
+        ground_plane: Some(GroundPlaneConfig::new(calibration).with_max_distance(1.0)),
+        ..TrackerConfig::default()
+    };
+    let mut tracker = BYTETracker::new(config);
+
+    // Moving 2 pixels (0.2 m) per frame at 30 fps is 6 m/s.
+    let mut tracks = Vec::new();
+    for frame in 0..30 {
+        let x = 100.0 + frame as f32 * 2.0;
+        tracks = tracker.update(vec![Detection::new(x, 100.0, x + 200.0, 300.0, 0.9)]);
+    }
+    assert_eq!(tracks.len(), 1);
+    let (vx, vy) = tracks[0].ground_velocity().unwrap();
+    assert!((vx - 6.0).abs() < 0.5, "vx = {}", vx);
+    assert!(vy.abs() < 0.5);
+    let (gx, _) = tracks[0].ground_position().unwrap();
+    assert!((gx - calibration.track_position(&tracks[0]).unwrap().0).abs() < 0.5);
+
+    // A 3 m jump still overlaps in the image but is gated on the ground.
+    let id = tracks[0].track_id;
+    let x = 100.0 + 30.0 * 2.0 + 30.0;
+    let tracks = tracker.update(vec![Detection::new(x, 100.0, x + 200.0, 300.0, 0.9)]);
+    assert!(tracks.iter().all(|t| t.track_id != id));
+}
+
+#[test]
+fn test_motion_accessors() {
+    let mut tracker = BYTETracker::new(TrackerConfig::default());
+
+    // Moving right and down by (3, 4) pixels per frame.
+    let mut tracks = Vec::new();
+    for frame in 0..40 {
+        let (x, y) = (frame as f32 * 3.0, frame as f32 * 4.0);
+        tracks = tracker.update(vec![Detection::new(x, y, x + 50.0, y + 100.0, 0.9)]);
+    }
+    let track = &tracks[0];
+
+    let velocity = track.velocity().unwrap();
+    assert!((velocity.vx - 3.0).abs() < 0.2 && (velocity.vy - 4.0).abs() < 0.2);
+    assert!((track.speed().unwrap() - 5.0).abs() < 0.2);
+    assert!((track.velocity_per_second(30.0).unwrap().speed() - 150.0).abs() < 6.0);
+    assert!((track.heading().unwrap() - 4f32.atan2(3.0)).abs() < 0.05);
+    assert!(track.scale_rate().unwrap().abs() < 0.01);
+
+    let ellipse = track.position_uncertainty(2.0).unwrap();
+    assert!(ellipse.semi_major >= ellipse.semi_minor && ellipse.semi_minor > 0.0);
+    assert!(track.velocity_uncertainty(1.0).unwrap().area() > 0.0);
+}
+
+#[test]
+fn test_forecast() {
+    let mut tracker = BYTETracker::new(TrackerConfig::default());
+    let mut tracks = Vec::new();
+    for frame in 0..20 {
+        let x = frame as f32 * 5.0;
+        tracks = tracker.update(vec![Detection::new(x, 0.0, x + 50.0, 100.0, 0.9)]);
+    }
+    let before = tracks[0].tlwh();
+
+    let forecasts = tracker.forecast(10);
+    assert_eq!(forecasts.len(), 1);
+    let (id, steps) = &forecasts[0];
+    assert_eq!(*id, tracks[0].track_id);
+    assert_eq!(steps.len(), 10);
+    assert!((steps[9].rect.x - (before.x + 50.0)).abs() < 2.0);
+    assert!(steps.windows(2).all(|w| {
+        w[1].position_uncertainty(1.0).area() > w[0].position_uncertainty(1.0).area()
+    }));
+
+    // Forecasting leaves tracker state untouched.
+    let again = tracker.forecast(10);
+    assert_eq!(again[0].1[9].rect.to_tlwh(), steps[9].rect.to_tlwh());
+}
+
+#[test]
+fn test_trajectory_history() {
+    let mut tracker = BYTETracker::new(TrackerConfig {
+        history_length: Some(5),
+        ..TrackerConfig::default()
+    });
+    let mut tracks = Vec::new();
+    for frame in 0..8 {
+        let x = frame as f32 * 4.0;
+        tracks = tracker.update(vec![Detection::new(x, 0.0, x + 50.0, 100.0, 0.9)]);
+    }
+    let frames: Vec<u32> = tracks[0].trajectory().map(|p| p.frame_id).collect();
+    assert_eq!(frames, vec![4, 5, 6, 7, 8]);
+    let last = tracks[0].trajectory().last().unwrap();
+    assert_eq!(last.observed.x, 28.0);
+    assert!((last.filtered.x - 28.0).abs() < 2.0);
+
+    // Disabled by default.
+    let mut tracker = BYTETracker::new(TrackerConfig::default());
+    let tracks = tracker.update(vec![Detection::new(0.0, 0.0, 50.0, 100.0, 0.9)]);
+    assert!(tracks[0].history.is_none());
+    assert_eq!(tracks[0].trajectory().count(), 0);
+}
+
+#[test]
+fn test_line_and_zone_analytics() {
+    use bytetrack_rs::analytics::{CountingLine, LineCounter, Polygon, ZoneCounter, ZoneEventKind};
+
+    let mut tracker = BYTETracker::new(TrackerConfig::default());
+    let mut line =
+        LineCounter::new(CountingLine::new((0.0, 300.0), (640.0, 300.0))).with_hysteresis(4.0);
+    let mut zone = ZoneCounter::new(Polygon::from_rect(&bytetrack_rs::Rect::new(
+        0.0, 300.0, 640.0, 180.0,
+    )));
+
+    // One person walks down through the door line, another walks up.
+    let mut zone_events = Vec::new();
+    for frame in 0..40 {
+        let down = 150.0 + frame as f32 * 6.0;
+        let up = 420.0 - frame as f32 * 6.0;
+        let tracks = tracker.update(vec![
+            Detection::new(100.0, down - 100.0, 150.0, down, 0.9),
+            Detection::new(400.0, up - 100.0, 450.0, up, 0.9),
+        ]);
+        line.update(&tracks, tracker.frame_id());
+        zone_events.extend(zone.update(&tracks, tracker.frame_id()));
+    }
+
+    assert_eq!((line.forward_count(), line.backward_count()), (1, 1));
+    let enters = zone_events
+        .iter()
+        .filter(|e| e.kind == ZoneEventKind::Enter)
+        .count();
+    assert_eq!(enters, 2);
+    assert_eq!(zone.occupancy(), 1);
+}
+
+#[test]
+fn test_region_filter() {
+    use bytetrack_rs::tracker::{Polygon, RegionFilter};
+
+    let region = RegionFilter::new()
+        .with_roi(Polygon::from_rect(&bytetrack_rs::Rect::new(
+            0.0, 0.0, 640.0, 480.0,
+        )))
+        .with_ignore(Polygon::from_rect(&bytetrack_rs::Rect::new(
+            500.0, 0.0, 140.0, 100.0,
+        )))
+        .with_border_band(640.0, 480.0, 40.0)
+        .with_remove_on_exit(true);
+    let mut tracker = BYTETracker::new(TrackerConfig {
+        region: Some(region),
+        ..TrackerConfig::default()
+    });
+
+    // A timestamp overlay, a box in the border band and a person walking
+    // from the middle out through the bottom edge.
+    let mut ids = Vec::new();
+    for frame in 0..40 {
+        let bottom = 300.0 + frame as f32 * 8.0;
+        let tracks = tracker.update(vec![
+            Detection::new(520.0, 10.0, 620.0, 40.0, 0.9),
+            Detection::new(5.0, 200.0, 35.0, 470.0, 0.9),
+            Detection::new(200.0, bottom - 150.0, 260.0, bottom, 0.9),
+        ]);
+        ids.push(tracks.iter().map(|t| t.track_id).collect::<Vec<_>>());
+    }
+
+    assert_eq!(ids[0].len(), 1);
+    assert!(ids[1..20].iter().all(|frame| frame == &ids[0]));
+    assert!(ids[39].is_empty());
+    let stats = tracker.stats();
+    assert_eq!(stats.tracks_started, 1);
+    assert!(stats.filtered_detections >= 40);
+    assert_eq!(stats.tracked + stats.lost, 0);
+}
+
+#[test]
+fn test_frame_boundary() {
+    let config = TrackerConfig {
+        out_of_frame_buffer: 3,
+        ..TrackerConfig::default()
+    };
+    let mut tracker = BYTETracker::new(config);
+
+    // Walk right, out of a 320 x 240 frame, and then vanish.
+    let mut last = Vec::new();
+    for frame in 0..20 {
+        let x = 200.0 + frame as f32 * 8.0;
+        last = tracker.update_in_frame(
+            vec![Detection::new(x, 50.0, x + 60.0, 150.0, 0.9)],
+            320,
+            240,
+        );
+    }
+    let clipped = last[0].output_tlwh();
+    assert!(clipped.x + clipped.width <= 320.0);
+    assert!(last[0].tlwh().x + last[0].tlwh().width > 320.0);
+
+    for _ in 0..5 {
+        tracker.update_in_frame(vec![], 320, 240);
+    }
+    let stats = tracker.stats();
+    assert_eq!((stats.lost, stats.tracks_removed), (0, 1));
+
+    // Without the frame size, the track stays lost for the full buffer.
+    let mut tracker = BYTETracker::new(TrackerConfig::default());
+    for frame in 0..20 {
+        let x = 200.0 + frame as f32 * 8.0;
+        tracker.update(vec![Detection::new(x, 50.0, x + 60.0, 150.0, 0.9)]);
+    }
+    for _ in 0..5 {
+        tracker.update(vec![]);
+    }
+    assert_eq!(tracker.stats().lost, 1);
+
+    // The last center is at x = 382, moving 8 px per frame, so it leaves a
+    // 410 px wide frame on the fourth frame without detections. This holds
+    // whether or not frames in between only ran `predict`.
+    for predict_only in [false, true] {
+        let mut tracker = BYTETracker::new(TrackerConfig {
+            out_of_frame_buffer: 1,
+            ..TrackerConfig::default()
+        });
+        for frame in 0..20 {
+            let x = 200.0 + frame as f32 * 8.0;
+            tracker.update_in_frame(
+                vec![Detection::new(x, 50.0, x + 60.0, 150.0, 0.9)],
+                410,
+                240,
+            );
+        }
+        for frame in 0..3 {
+            if predict_only && frame == 1 {
+                tracker.predict();
+            } else {
+                tracker.update_in_frame(vec![], 410, 240);
+            }
+        }
+        assert_eq!(tracker.stats().lost, 1);
+        tracker.update_in_frame(vec![], 410, 240);
+        assert_eq!(tracker.stats().lost, 0);
+    }
+
+    // Tracks from the prediction fallback are clipped too; an infinite
+    // score makes the assignment fail.
+    let mut tracker = BYTETracker::new(TrackerConfig::default());
+    tracker.update_in_frame(
+        vec![Detection::new(280.0, 50.0, 340.0, 150.0, 0.9)],
+        320,
+        240,
+    );
+    let fallback = tracker.update_in_frame(
+        vec![Detection::new(280.0, 50.0, 340.0, 150.0, f32::INFINITY)],
+        320,
+        240,
+    );
+    assert_eq!(fallback[0].frame_size, Some((320, 240)));
+    assert_eq!(
+        fallback[0].output_tlwh().x + fallback[0].output_tlwh().width,
+        320.0
+    );
+}
+
+#[test]
+fn test_oriented_box_tracking() {
+    use bytetrack_rs::tracker::RotatedRect;
+
+    let mut tracker = BYTETracker::<RotatedRect>::new(TrackerConfig::default());
+
+    // A long box spinning through the +-pi/2 wrap, and a crossed box next
+    // to it whose axis-aligned envelope overlaps but whose rotated IoU is low.
+    let mut ids = Vec::new();
+    let mut tracks = Vec::new();
+    for frame in 0..30 {
+        let angle = 1.0 + frame as f32 * 0.05;
+        let x = 100.0 + frame as f32 * 2.0;
+        tracks = tracker.update(vec![
+            Detection::from_rect(RotatedRect::new(x, 100.0, 120.0, 20.0, angle), 0.9),
+            Detection::from_rect(RotatedRect::new(x + 40.0, 100.0, 120.0, 20.0, -angle), 0.9),
+        ]);
+        let mut frame_ids: Vec<u64> = tracks.iter().map(|t| t.track_id).collect();
+        frame_ids.sort_unstable();
+        ids.push(frame_ids);
+    }
+
+    assert_eq!(ids[0].len(), 2);
+    assert!(ids.iter().all(|frame| frame == &ids[0]));
+    let spinning = tracks
+        .iter()
+        .find(|t| t.angular_rate().unwrap() > 0.0)
+        .unwrap();
+    assert!((spinning.angular_rate().unwrap() - 0.05).abs() < 0.01);
+    assert!(spinning.tlwh().angle < 0.0);
+}
+
+#[test]
+fn test_mask_iou_association() {
+    use bytetrack_rs::tracker::RleMask;
+
+    // Two thin diagonal objects, "\" and "/", sharing one bounding box.
+    let (h, w) = (120u32, 160u32);
+    let band = |x0: usize, falling: bool| -> RleMask {
+        let bitmap: Vec<bool> = (0..(h * w) as usize)
+            .map(|i| {
+                let (r, c) = (i / w as usize, i % w as usize);
+                if !(x0..x0 + 100).contains(&c) || !(10..110).contains(&r) {
+                    return false;
+                }
+                let d = if falling { c - x0 } else { x0 + 99 - c };
+                (r as i64 - 10 - d as i64).abs() <= 4
+            })
+            .collect();
+        RleMask::from_bitmap(h, w, &bitmap).unwrap()
+    };
+
+    let frames = || {
+        (0..10).map(|frame| {
+            let x0 = 10 + frame * 2;
+            let x = x0 as f32;
+            vec![
+                Detection::new(x, 10.0, x + 100.0, 110.0, 0.9).with_mask(band(x0, true)),
+                Detection::new(x, 10.0, x + 100.0, 110.0, 0.9).with_mask(band(x0, false)),
+            ]
+        })
+    };
+    let falling =
+        |frame: usize, t: &STrack| t.mask.as_ref().unwrap().iou(&band(10 + frame * 2, true)) > 0.9;
+    let run = |mask_weight: f32| {
+        let config = TrackerConfig {
+            mask_weight,
+            ..TrackerConfig::default()
+        };
+        keeps_identity(config, frames(), falling)
+    };
+
+    assert!(run(0.5));
+    assert!(!run(0.0));
+}
+
+#[test]
+fn test_keypoint_association() {
+    use bytetrack_rs::tracker::{Keypoint, KeypointConfig};
+
+    // Two people sharing one bounding box, one on each side of it.
+    let pose = |cx: f32| -> Vec<Keypoint> {
+        (0..17)
+            .map(|k| Keypoint::new(cx + (k % 3) as f32, 20.0 + 5.0 * k as f32, 0.9))
+            .collect()
+    };
+
+    let x = |frame: usize| 10.0 + frame as f32 * 2.0;
+    let frames = || {
+        (0..10).map(|frame| {
+            let x = x(frame);
+            vec![
+                Detection::new(x, 10.0, x + 100.0, 110.0, 0.9).with_keypoints(pose(x + 20.0)),
+                Detection::new(x, 10.0, x + 100.0, 110.0, 0.9).with_keypoints(pose(x + 80.0)),
+            ]
+        })
+    };
+    let left = |frame: usize, t: &STrack| t.keypoints.as_ref().unwrap()[0].x < x(frame) + 50.0;
+    let run = |keypoints: Option<KeypointConfig>| {
+        let config = TrackerConfig {
+            keypoints,
+            ..TrackerConfig::default()
+        };
+        keeps_identity(config, frames(), left)
+    };
+
+    assert!(run(Some(KeypointConfig::default())));
+    assert!(!run(None));
+
+    // Smoothed keypoints lag behind a keypoint that jumps.
+    let mut tracker = BYTETracker::new(TrackerConfig {
+        keypoints: Some(KeypointConfig::default().with_smoothing(0.5)),
+        ..TrackerConfig::default()
+    });
+    for cx in [50.0, 50.0, 60.0] {
+        let det = Detection::new(10.0, 10.0, 110.0, 110.0, 0.9).with_keypoints(pose(cx));
+        tracker.update(vec![det]);
+    }
+    let tracks = tracker.update(vec![
+        Detection::new(10.0, 10.0, 110.0, 110.0, 0.9).with_keypoints(pose(60.0)),
+    ]);
+    let smoothed = tracks[0].smoothed_keypoints.as_ref().unwrap();
+    assert!((smoothed[0].x - 57.5).abs() < 1e-4);
+    assert_eq!(tracks[0].keypoints.as_ref().unwrap()[0].x, 60.0);
+}
+
+#[test]
+fn test_box3d_tracking() {
+    use bytetrack_rs::tracker::{Affinity, Box3D};
+
+    // Two cars driving in parallel lanes; one drops to a low score for a
+    // few frames and must be recovered by the second association stage.
+    let mut tracker: BYTETracker<Box3D> = BYTETracker::new(TrackerConfig::default());
+    let mut ids = None;
+    for frame in 0..20 {
+        let x = frame as f32;
+        let low = (8..12).contains(&frame);
+        let dets = vec![
+            Detection::from_rect(Box3D::new(x, 0.0, 0.75, 4.5, 1.8, 1.5, 0.0), 0.9),
+            Detection::from_rect(
+                Box3D::new(x, 3.5, 0.75, 4.5, 1.8, 1.5, 0.0),
+                if low { 0.3 } else { 0.9 },
+            ),
+        ];
+        let mut tracks = tracker.update(dets);
+        assert_eq!(tracks.len(), 2, "frame {}", frame);
+        tracks.sort_by(|a, b| a.tlwh().y.total_cmp(&b.tlwh().y));
+        let frame_ids = (tracks[0].track_id, tracks[1].track_id);
+        assert_eq!(*ids.get_or_insert(frame_ids), frame_ids);
+        if frame == 19 {
+            // Velocity is in ground-plane units per frame.
+            let v = tracks[0].velocity().unwrap();
+            assert!((v.vx - 1.0).abs() < 0.1 && v.vy.abs() < 0.1);
+        }
+    }
+
+    // A pedestrian walks a body width per frame, so consecutive boxes never
+    // overlap. With IoU the first track is lost straight away and its
+    // successors are never confirmed; center distance keeps one track.
+    let run = |affinity: Affinity| {
+        let config = TrackerConfig {
+            affinity,
+            ..TrackerConfig::default()
+        };
+        let frames = (0..10).map(|frame| {
+            let det = Box3D::new(0.0, frame as f32 * 0.8, 0.85, 0.6, 0.6, 1.7, 0.0);
+            vec![Detection::from_rect(det, 0.9)]
+        });
+        keeps_identity(config, frames, |_, _| true)
+    };
+    assert!(!run(Affinity::Iou));
+    assert!(run(Affinity::CenterDistance { max_distance: 2.0 }));
+}
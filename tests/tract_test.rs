@@ -0,0 +1,59 @@
+#![cfg(feature = "onnx-tract")]
+
+//! Tests for the tract ONNX backend.
+//!
+//! `data/tiny_yolo.onnx` takes a `[1, 3, 8, 8]` image and emits two
+//! YOLOv5-style rows (one class). Both boxes heavily overlap and their
+//! objectness equals the mean input intensity (x1.0 and x0.9).
+
+use bytetrack_rs::{
+    DetectionSource, ImageError, TractDetector, TractDetectorError, YoloDecoder, YoloVersion,
+};
+
+const MODEL: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/tiny_yolo.onnx");
+
+fn detector() -> TractDetector {
+    TractDetector::from_path(MODEL, YoloDecoder::new(YoloVersion::V5, 1)).unwrap()
+}
+
+#[test]
+fn test_tract_loads_input_size() {
+    assert_eq!(detector().input_size(), (3, 8, 8));
+}
+
+#[test]
+fn test_tract_detect_letterboxed_frame() {
+    let mut detector = detector();
+
+    // 16x16 white frame is downscaled by 2, so boxes scale back up by 2.
+    let frame = vec![255u8; 16 * 16 * 3];
+    let dets = detector.detect(&frame, 16, 16).unwrap();
+
+    // The overlapping second row is removed by NMS.
+    assert_eq!(dets.len(), 1);
+    assert!((dets[0].score - 1.0).abs() < 1e-5);
+    assert_eq!(dets[0].class_id, Some(0));
+    assert_eq!(dets[0].bbox.to_tlbr(), [4.0, 4.0, 12.0, 12.0]);
+}
+
+#[test]
+fn test_tract_dark_frame_has_no_detections() {
+    let mut detector = detector();
+    let frame = vec![0u8; 8 * 8 * 3];
+    assert!(detector.detect(&frame, 8, 8).unwrap().is_empty());
+}
+
+#[test]
+fn test_tract_rejects_bad_input() {
+    let mut detector = detector();
+    assert!(detector.detect(&[0u8; 10], 8, 8).is_err());
+}
+
+#[test]
+fn test_tract_rejects_empty_frame() {
+    let mut detector = detector();
+    assert!(matches!(
+        detector.detect(&[], 0, 0),
+        Err(TractDetectorError::InvalidImage(ImageError::Empty))
+    ));
+}